@@ -0,0 +1,143 @@
+//! Trait over the request surface of [`Esi`], for substituting a fake in tests.
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::prelude::*;
+
+/// The core request surface of [`Esi`], as a trait.
+///
+/// Code that takes a `&dyn EsiApi` (or `Box<dyn EsiApi>`) rather than an
+/// `&Esi` can be tested against a fake that returns canned responses.
+/// Typed responses are available through [`EsiApiExt::query_as`] and
+/// [`EsiApiExt::query_op`].
+///
+/// # Example
+/// ```rust
+/// use async_trait::async_trait;
+/// use rfesi::prelude::*;
+/// use serde::Deserialize;
+/// use serde_json::{json, Value};
+///
+/// struct FakeEsi;
+///
+/// #[async_trait]
+/// impl EsiApi for FakeEsi {
+///     async fn query_value(
+///         &self,
+///         _method: &str,
+///         _request_type: RequestType,
+///         endpoint: &str,
+///         _query: Option<&[(&str, &str)]>,
+///         _body: Option<&str>,
+///     ) -> EsiResult<Value> {
+///         match endpoint {
+///             "latest/status/" => Ok(json!({
+///                 "players": 20000,
+///                 "server_version": "2000000",
+///                 "start_time": "2024-01-01T11:00:00Z"
+///             })),
+///             _ => Err(EsiError::InvalidStatusCode { status: 404, request_id: None }),
+///         }
+///     }
+///
+///     fn endpoint_for_op_id(&self, op_id: &str) -> EsiResult<String> {
+///         match op_id {
+///             "get_status" => Ok("latest/status/".to_owned()),
+///             _ => Err(EsiError::UnknownOperationID(op_id.to_owned())),
+///         }
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct ServerStatus {
+///     players: i32,
+/// }
+///
+/// async fn player_count(esi: &dyn EsiApi) -> EsiResult<i32> {
+///     let status: ServerStatus =
+///         esi.query_op("GET", RequestType::Public, "get_status", None, None).await?;
+///     Ok(status.players)
+/// }
+/// # #[tokio::main]
+/// # async fn main() {
+/// assert_eq!(player_count(&FakeEsi).await.unwrap(), 20000);
+/// # }
+/// ```
+#[async_trait]
+pub trait EsiApi: Send + Sync {
+    /// Make a request, returning the response body as JSON.
+    ///
+    /// See [`Esi::query`] for the arguments.
+    async fn query_value(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<&str>,
+    ) -> EsiResult<Value>;
+
+    /// Resolve an operationId to its endpoint path, as used by the
+    /// endpoint groups.
+    fn endpoint_for_op_id(&self, op_id: &str) -> EsiResult<String>;
+}
+
+#[async_trait]
+impl EsiApi for Esi {
+    async fn query_value(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<&str>,
+    ) -> EsiResult<Value> {
+        self.query(method, request_type, endpoint, query, body)
+            .await
+    }
+
+    fn endpoint_for_op_id(&self, op_id: &str) -> EsiResult<String> {
+        self.get_wrapped_endpoint(op_id)
+            .map(|path| path.into_owned())
+    }
+}
+
+/// Typed requests for any [`EsiApi`], including `dyn EsiApi`.
+#[async_trait]
+pub trait EsiApiExt: EsiApi {
+    /// Make a request, deserializing the response body.
+    async fn query_as<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<&str>,
+    ) -> EsiResult<T> {
+        let value = self
+            .query_value(method, request_type, endpoint, query, body)
+            .await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Make a request to the endpoint for an operationId, deserializing
+    /// the response body.
+    ///
+    /// The endpoint must not have any path parameters.
+    async fn query_op<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        op_id: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<&str>,
+    ) -> EsiResult<T> {
+        let endpoint = self.endpoint_for_op_id(op_id)?;
+        self.query_as(method, request_type, &endpoint, query, body)
+            .await
+    }
+}
+
+impl<A: EsiApi + ?Sized> EsiApiExt for A {}
@@ -3,7 +3,43 @@
 use crate::prelude::*;
 use reqwest::{header, Client};
 use serde_json::Value;
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+/// An EVE server cluster to point the `Esi` struct at.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum EsiEnvironment {
+    /// The live server.
+    Tranquility,
+    /// The public test server.
+    Singularity,
+}
+
+impl EsiEnvironment {
+    /// Get the `(base_api_url, authorize_url, token_url, spec_url)` for this environment.
+    fn urls(&self, version: &str) -> (String, String, String, String) {
+        let login_host = match self {
+            EsiEnvironment::Tranquility => "login.eveonline.com",
+            EsiEnvironment::Singularity => "sisilogin.testeveonline.com",
+        };
+        (
+            "https://esi.evetech.net/".to_owned(),
+            format!("https://{login_host}/v2/oauth/authorize"),
+            format!("https://{login_host}/v2/oauth/token"),
+            format!(
+                "https://esi.evetech.net/_{version}/swagger.json?datasource={}",
+                self.datasource()
+            ),
+        )
+    }
+
+    /// Get the value of the `datasource` query parameter for this environment.
+    pub(crate) fn datasource(&self) -> &'static str {
+        match self {
+            EsiEnvironment::Tranquility => "tranquility",
+            EsiEnvironment::Singularity => "singularity",
+        }
+    }
+}
 
 /// Builder for the `Esi` struct.
 ///
@@ -11,7 +47,7 @@ use std::time::Duration;
 ///
 /// ```rust
 /// # use rfesi::prelude::EsiBuilder;
-/// let mut esi = EsiBuilder::new()
+/// let esi = EsiBuilder::new()
 ///     .user_agent("some user agent")
 ///     .client_id("your_client_id")
 ///     .client_secret("your_client_secret")
@@ -32,13 +68,31 @@ use std::time::Duration;
 /// ```rust
 /// # use rfesi::prelude::EsiBuilder;
 /// # let your_spec = serde_json::json!({});
-/// let mut esi = EsiBuilder::new()
+/// let esi = EsiBuilder::new()
 ///     .user_agent("some user agent")
 ///     .spec(Some(your_spec))
 ///     .build()
 ///     .unwrap();
 /// ```
 ///
+/// To share one copy of the spec between structs rather than cloning
+/// it for each, pass it along with `shared_spec`:
+///
+/// ```rust
+/// # use rfesi::prelude::EsiBuilder;
+/// # let your_spec = serde_json::json!({});
+/// # let esi = EsiBuilder::new()
+/// #     .user_agent("some user agent")
+/// #     .spec(Some(your_spec))
+/// #     .build()
+/// #     .unwrap();
+/// let other = EsiBuilder::new()
+///     .user_agent("some user agent")
+///     .shared_spec(esi.shared_spec())
+///     .build()
+///     .unwrap();
+/// ```
+///
 /// Note that this "spec" function is just another builder
 /// function; you can make use it alongside all of the others.
 /// Note also that this is entirely optional: if you don't
@@ -56,7 +110,7 @@ use std::time::Duration;
 ///
 /// ```rust
 /// # use rfesi::prelude::EsiBuilder;
-/// let mut esi = EsiBuilder::new()
+/// let esi = EsiBuilder::new()
 ///     .user_agent("some user agent")
 ///     .build()
 ///     .unwrap();
@@ -64,6 +118,21 @@ use std::time::Duration;
 ///
 /// Note that you still need to set the user agent, as this is good
 /// API usage behavior.
+///
+/// # Targeting another server
+///
+/// To point the struct at the test server, set the environment,
+/// which sets all of the URLs at once. Any of them can still be
+/// overridden afterward:
+///
+/// ```rust
+/// # use rfesi::prelude::{EsiBuilder, EsiEnvironment};
+/// let esi = EsiBuilder::new()
+///     .user_agent("some user agent")
+///     .environment(EsiEnvironment::Singularity)
+///     .build()
+///     .unwrap();
+/// ```
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct EsiBuilder {
     pub(crate) version: Option<String>,
@@ -75,13 +144,38 @@ pub struct EsiBuilder {
     pub(crate) authorize_url: Option<String>,
     pub(crate) token_url: Option<String>,
     pub(crate) spec_url: Option<String>,
+    pub(crate) spec_url_pattern: Option<String>,
     pub(crate) scope: Option<String>,
     pub(crate) access_token: Option<String>,
     pub(crate) access_expiration: Option<i64>,
     pub(crate) refresh_token: Option<String>,
     pub(crate) user_agent: Option<String>,
     pub(crate) http_timeout: Option<u64>,
-    pub(crate) spec: Option<Value>,
+    pub(crate) spec: Option<Arc<Value>>,
+    pub(crate) environment: Option<EsiEnvironment>,
+    pub(crate) warn_on_deprecated: Option<bool>,
+    pub(crate) log_http_errors: Option<bool>,
+    pub(crate) suppressed_log_statuses: Option<Vec<u16>>,
+    pub(crate) compatibility_date: Option<String>,
+    pub(crate) cached_ops: Option<HashMap<String, Duration>>,
+    pub(crate) pool_max_idle_per_host: Option<usize>,
+    pub(crate) pool_idle_timeout: Option<Duration>,
+    pub(crate) http2_keep_alive_interval: Option<Duration>,
+    pub(crate) http2_adaptive_window: Option<bool>,
+    pub(crate) accept: Option<String>,
+    #[serde(skip)]
+    pub(crate) clock: Option<Clock>,
+}
+
+/// Function returning the current time in milliseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, Eq)]
+pub(crate) struct Clock(pub(crate) fn() -> i64);
+
+/// Compared by address, which is enough to tell builders apart.
+impl PartialEq for Clock {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::fn_addr_eq(self.0, other.0)
+    }
 }
 
 impl EsiBuilder {
@@ -90,6 +184,59 @@ impl EsiBuilder {
         Default::default()
     }
 
+    /// Start a new builder from environment variables.
+    ///
+    /// Reads:
+    ///
+    /// - `ESI_USER_AGENT` (required)
+    /// - `ESI_CLIENT_ID` (required)
+    /// - `ESI_CALLBACK_URL` (required)
+    /// - `ESI_CLIENT_SECRET`; if not set, application (PKCE)
+    ///   authentication is enabled instead
+    /// - `ESI_SCOPE`
+    ///
+    /// Any of the builder's methods can be called afterward to override
+    /// these. If a required variable is not set, the error lists all of
+    /// the missing variables.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rfesi::prelude::*;
+    /// let esi = EsiBuilder::from_env()
+    ///     .unwrap()
+    ///     .scope("esi-skills.read_skills.v1")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn from_env() -> EsiResult<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Start a new builder from variables looked up by name.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> EsiResult<Self> {
+        let required = ["ESI_USER_AGENT", "ESI_CLIENT_ID", "ESI_CALLBACK_URL"];
+        let missing: Vec<String> = required
+            .iter()
+            .filter(|name| var(name).is_none())
+            .map(|name| name.to_string())
+            .collect();
+        if !missing.is_empty() {
+            return Err(EsiError::MissingEnvVars(missing));
+        }
+        let mut builder = EsiBuilder::new()
+            .user_agent(&var("ESI_USER_AGENT").unwrap_or_default())
+            .client_id(&var("ESI_CLIENT_ID").unwrap_or_default())
+            .callback_url(&var("ESI_CALLBACK_URL").unwrap_or_default());
+        builder = match var("ESI_CLIENT_SECRET") {
+            Some(secret) => builder.client_secret(&secret),
+            None => builder.enable_application_authentication(true),
+        };
+        if let Some(scope) = var("ESI_SCOPE") {
+            builder = builder.scope(&scope);
+        }
+        Ok(builder)
+    }
+
     /// Set the version of ESI to use.
     ///
     /// Will default to `"latest"` if not set.
@@ -141,11 +288,53 @@ impl EsiBuilder {
     }
 
     /// Set the spec_url.
+    ///
+    /// Takes precedence over [`EsiBuilder::spec_url_pattern`].
     pub fn spec_url(mut self, val: &str) -> Self {
         self.spec_url = Some(val.to_owned());
         self
     }
 
+    /// Set the pattern that the spec_url is built from, where `{version}`
+    /// is replaced with the version of ESI in use.
+    ///
+    /// Will default to `"https://esi.evetech.net/_{version}/swagger.json"`
+    /// if not set. Useful if CCP moves the spec to another path. Ignored if
+    /// the spec_url is set, either directly or by
+    /// [`EsiBuilder::environment`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use rfesi::prelude::*;
+    /// let esi = EsiBuilder::new()
+    ///     .user_agent("some user agent")
+    ///     .version("dev")
+    ///     .spec_url_pattern("https://esi.evetech.net/meta/{version}/swagger.json")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn spec_url_pattern(mut self, val: &str) -> Self {
+        self.spec_url_pattern = Some(val.to_owned());
+        self
+    }
+
+    /// Set the server environment, setting the base_api_url,
+    /// authorize_url, token_url, and spec_url all at once.
+    ///
+    /// Requests to ESI will include the matching `datasource`
+    /// query parameter. Call this before setting the version,
+    /// or any individual URLs that you want to override.
+    pub fn environment(mut self, val: EsiEnvironment) -> Self {
+        let version = self.version.as_deref().unwrap_or("latest");
+        let (base_api_url, authorize_url, token_url, spec_url) = val.urls(version);
+        self.base_api_url = Some(base_api_url);
+        self.authorize_url = Some(authorize_url);
+        self.token_url = Some(token_url);
+        self.spec_url = Some(spec_url);
+        self.environment = Some(val);
+        self
+    }
+
     /// Set the scope.
     pub fn scope(mut self, val: &str) -> Self {
         self.scope = Some(val.to_owned().replace(' ', "%20"));
@@ -176,6 +365,33 @@ impl EsiBuilder {
         self
     }
 
+    /// Set the `Accept` header sent with every request, or `None`
+    /// to not send one.
+    ///
+    /// Will default to `application/json` if not set.
+    pub fn accept_header(mut self, val: Option<&str>) -> Self {
+        // an empty value is stored to mean "no header"
+        self.accept = Some(val.unwrap_or_default().to_owned());
+        self
+    }
+
+    /// Set the function used to get the current time, in milliseconds
+    /// since the Unix epoch.
+    ///
+    /// The struct uses it for every check against the current time: the
+    /// access token's expiration, the expiry of cached responses and
+    /// insurance prices, and [`Esi::now`](crate::prelude::Esi::now) for the
+    /// time-based helpers on response structs. Request durations in
+    /// [`EsiResponse::elapsed`](crate::prelude::EsiResponse::elapsed) are
+    /// still measured with the system's monotonic clock.
+    ///
+    /// Will default to the system time if not set. Mostly useful in tests,
+    /// to control time without waiting. This is not serialized.
+    pub fn clock(mut self, val: fn() -> i64) -> Self {
+        self.clock = Some(Clock(val));
+        self
+    }
+
     /// Set the timeout to use in millis when sending HTTP requests.
     ///
     /// Will default to 60,000 (1 minute) if not set.
@@ -184,6 +400,100 @@ impl EsiBuilder {
         self
     }
 
+    /// Set the maximum number of idle connections kept open to each host.
+    ///
+    /// Will default to reqwest's default (no limit) if not set.
+    pub fn pool_max_idle_per_host(mut self, val: usize) -> Self {
+        self.pool_max_idle_per_host = Some(val);
+        self
+    }
+
+    /// Set how long an idle connection is kept open before being closed.
+    ///
+    /// Will default to reqwest's default (90 seconds) if not set.
+    pub fn pool_idle_timeout(mut self, val: Duration) -> Self {
+        self.pool_idle_timeout = Some(val);
+        self
+    }
+
+    /// Set the interval at which HTTP/2 pings are sent to keep connections alive.
+    ///
+    /// Pings are not sent if not set.
+    pub fn http2_keep_alive_interval(mut self, val: Duration) -> Self {
+        self.http2_keep_alive_interval = Some(val);
+        self
+    }
+
+    /// Set whether to use HTTP/2's adaptive flow control, which can improve
+    /// throughput for large responses like market and asset dumps.
+    ///
+    /// Will default to `false` if not set.
+    pub fn http2_adaptive_window(mut self, val: bool) -> Self {
+        self.http2_adaptive_window = Some(val);
+        self
+    }
+
+    /// Set whether to log a warning the first time a deprecated
+    /// endpoint is called.
+    ///
+    /// ESI marks responses from deprecated endpoints with a `warning`
+    /// header. Will default to `true` if not set.
+    pub fn warn_on_deprecated(mut self, val: bool) -> Self {
+        self.warn_on_deprecated = Some(val);
+        self
+    }
+
+    /// Set whether to log a warning when ESI or SSO responds with an
+    /// error status.
+    ///
+    /// For ESI requests, the message includes the status, the endpoint's
+    /// operationId, and the URL. Will default to `true` if not set.
+    pub fn log_http_errors(mut self, val: bool) -> Self {
+        self.log_http_errors = Some(val);
+        self
+    }
+
+    /// Set HTTP status codes to not log a warning for, such as 404 for
+    /// callers that treat a missing resource as an expected result.
+    ///
+    /// The error is still returned; only the log message is skipped.
+    /// Has no effect if [`EsiBuilder::log_http_errors`] is `false`.
+    pub fn suppress_log_for_statuses(mut self, val: &[u16]) -> Self {
+        self.suppressed_log_statuses = Some(val.to_vec());
+        self
+    }
+
+    /// Set the compatibility date to send with every request, as a
+    /// `YYYY-MM-DD` string.
+    ///
+    /// CCP is moving ESI away from versioned paths (`/v5/...`) to
+    /// unversioned paths that take an `X-Compatibility-Date` header,
+    /// which pins the response format to what it was on that date.
+    /// When set, that header is sent on every request.
+    ///
+    /// Only the header is added: the paths are not changed, so requests
+    /// still go to the versioned (or `latest`) paths from the spec, and
+    /// setting this alone does not switch to the unversioned ESI. To
+    /// target it, pass unversioned paths to [`Esi::query`] yourself.
+    pub fn compatibility_date(mut self, val: &str) -> Self {
+        self.compatibility_date = Some(val.to_owned());
+        self
+    }
+
+    /// Cache successful responses from an endpoint in memory for a time.
+    ///
+    /// Useful for small endpoints that are polled often, such as `get_status`,
+    /// which ESI only updates periodically anyway. Only public GET requests
+    /// are cached, keyed by their path and query. The cache is shared between
+    /// clones of the built `Esi` struct. Can be called more than once to
+    /// cache several endpoints.
+    pub fn cache_op(mut self, op_id: &str, ttl: Duration) -> Self {
+        self.cached_ops
+            .get_or_insert_with(HashMap::new)
+            .insert(op_id.to_owned(), ttl);
+        self
+    }
+
     /// Explicitly set the OpenAPI specification.
     ///
     /// Allows copying the spec from another `Esi` struct
@@ -193,6 +503,16 @@ impl EsiBuilder {
     ///
     /// Be aware of the potential for out-of-date data.
     pub fn spec(mut self, spec: Option<Value>) -> Self {
+        self.spec = spec.map(Arc::new);
+        self
+    }
+
+    /// Set the OpenAPI specification, sharing it rather than copying it.
+    ///
+    /// Like [`EsiBuilder::spec`], but for a spec that is already behind
+    /// an `Arc`, such as one from [`Esi::shared_spec`]. The spec is
+    /// several megabytes, so sharing it saves a copy per struct.
+    pub fn shared_spec(mut self, spec: Option<Arc<Value>>) -> Self {
         self.spec = spec;
         self
     }
@@ -213,25 +533,41 @@ impl EsiBuilder {
                 header::USER_AGENT,
                 header::HeaderValue::from_str(user_agent)?,
             );
-            map.insert(
-                header::ACCEPT,
-                header::HeaderValue::from_static("application/json"),
-            );
+            match self.accept.as_deref() {
+                None => {
+                    map.insert(
+                        header::ACCEPT,
+                        header::HeaderValue::from_static("application/json"),
+                    );
+                }
+                Some("") => {}
+                Some(accept) => {
+                    map.insert(header::ACCEPT, header::HeaderValue::from_str(accept)?);
+                }
+            }
+            if let Some(date) = &self.compatibility_date {
+                map.insert("x-compatibility-date", header::HeaderValue::from_str(date)?);
+            }
             map
         };
-        #[cfg(not(feature = "rustls-tls"))]
-        let client = Client::builder()
+        let mut builder = Client::builder()
             .timeout(http_timeout)
-            .default_headers(headers)
-            .build()?;
-
+            .default_headers(headers);
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval);
+        }
+        if let Some(adaptive) = self.http2_adaptive_window {
+            builder = builder.http2_adaptive_window(adaptive);
+        }
         #[cfg(feature = "rustls-tls")]
-        let client = Client::builder()
-            .timeout(http_timeout)
-            .default_headers(headers)
-            .use_rustls_tls()
-            .build()?;
-        Ok(client)
+        let builder = builder.use_rustls_tls();
+        Ok(builder.build()?)
     }
 
     /// Construct the `Esi` instance.
@@ -246,7 +582,30 @@ impl EsiBuilder {
 
 #[cfg(test)]
 mod tests {
-    use super::EsiBuilder;
+    use super::{EsiBuilder, EsiEnvironment};
+    use crate::prelude::EsiError;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_builder_from_vars() {
+        let vars = HashMap::from([
+            ("ESI_USER_AGENT", "some user agent"),
+            ("ESI_CLIENT_ID", "a"),
+            ("ESI_CALLBACK_URL", "c"),
+        ]);
+        let b = EsiBuilder::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(b.client_id.as_deref(), Some("a"));
+        assert_eq!(b.client_secret, None);
+        assert_eq!(b.application_auth, Some(true));
+
+        let vars = HashMap::from([("ESI_CLIENT_ID", "a")]);
+        match EsiBuilder::from_vars(|name| vars.get(name).map(|v| v.to_string())) {
+            Err(EsiError::MissingEnvVars(missing)) => {
+                assert_eq!(missing, vec!["ESI_USER_AGENT", "ESI_CALLBACK_URL"]);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
 
     #[test]
     fn test_builder_valid() {
@@ -262,7 +621,7 @@ mod tests {
         assert_eq!(b.client_secret, Some(String::from("b")));
         assert_eq!(b.callback_url, Some(String::from("c")));
         assert_eq!(b.version, "latest");
-        assert_eq!(b.access_token, None);
+        assert_eq!(b.access_token(), None);
         assert_eq!(b.spec, None);
     }
 
@@ -281,7 +640,7 @@ mod tests {
         assert_eq!(b.token_url, "https://login.eveonline.com/v2/oauth/token");
         assert_eq!(b.spec_url, "https://esi.evetech.net/_latest/swagger.json");
         assert_eq!(b.version, "latest");
-        assert_eq!(b.access_token, None);
+        assert_eq!(b.access_token(), None);
         assert_eq!(b.spec, None);
     }
 
@@ -302,6 +661,47 @@ mod tests {
         assert_eq!(b.spec_url, "http://spec-url/");
     }
 
+    #[test]
+    fn test_builder_spec_url_pattern() {
+        let b = EsiBuilder::new()
+            .user_agent("d")
+            .version("dev")
+            .spec_url_pattern("https://esi.evetech.net/meta/{version}/swagger.json")
+            .build()
+            .unwrap();
+        assert_eq!(b.spec_url, "https://esi.evetech.net/meta/dev/swagger.json");
+
+        let b = EsiBuilder::new()
+            .user_agent("d")
+            .spec_url_pattern("https://esi.evetech.net/meta/{version}/swagger.json")
+            .spec_url("http://spec-url/")
+            .build()
+            .unwrap();
+        assert_eq!(b.spec_url, "http://spec-url/");
+    }
+
+    #[test]
+    fn test_builder_environment() {
+        let b = EsiBuilder::new()
+            .user_agent("d")
+            .environment(EsiEnvironment::Singularity)
+            .token_url("http://token-url")
+            .build()
+            .unwrap();
+
+        assert_eq!(b.base_api_url, "https://esi.evetech.net/");
+        assert_eq!(
+            b.authorize_url,
+            "https://sisilogin.testeveonline.com/v2/oauth/authorize"
+        );
+        assert_eq!(b.token_url, "http://token-url");
+        assert_eq!(
+            b.spec_url,
+            "https://esi.evetech.net/_latest/swagger.json?datasource=singularity"
+        );
+        assert_eq!(b.datasource, Some("singularity"));
+    }
+
     #[test]
     fn test_builder_missing_value() {
         let res = EsiBuilder::new().build();
@@ -329,12 +729,12 @@ mod tests {
             .build()
             .unwrap();
 
-        assert_eq!(spec, b.spec.unwrap());
+        assert_eq!(spec, *b.spec.unwrap());
     }
 
     #[test]
     fn test_builder_to_json_empty() {
-        let json = r#"{"version":null,"client_id":null,"client_secret":null,"application_auth":null,"callback_url":null,"base_api_url":null,"authorize_url":null,"token_url":null,"spec_url":null,"scope":null,"access_token":null,"access_expiration":null,"refresh_token":null,"user_agent":null,"http_timeout":null,"spec":null}"#;
+        let json = r#"{"version":null,"client_id":null,"client_secret":null,"application_auth":null,"callback_url":null,"base_api_url":null,"authorize_url":null,"token_url":null,"spec_url":null,"spec_url_pattern":null,"scope":null,"access_token":null,"access_expiration":null,"refresh_token":null,"user_agent":null,"http_timeout":null,"spec":null,"environment":null,"warn_on_deprecated":null,"log_http_errors":null,"suppressed_log_statuses":null,"compatibility_date":null,"cached_ops":null,"pool_max_idle_per_host":null,"pool_idle_timeout":null,"http2_keep_alive_interval":null,"http2_adaptive_window":null,"accept":null}"#;
         assert_eq!(json, serde_json::to_string(&EsiBuilder::new()).unwrap());
     }
 
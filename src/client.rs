@@ -1,50 +1,45 @@
 //! Main logic
 
 use base64::engine::{general_purpose::STANDARD as base64, Engine};
+use chrono::{DateTime, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use log::{debug, error, warn};
 #[cfg(feature = "random_state")]
 use rand::{distributions::Alphanumeric, Rng};
 use reqwest::{
     header::{self, HeaderMap, HeaderValue},
-    Client, Method,
+    Client, Method, RequestBuilder,
 };
-use serde::de::DeserializeOwned;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde_json::Value;
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt,
     str::FromStr,
-    time::{SystemTime, UNIX_EPOCH},
+    sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use crate::builders::Clock;
+use crate::errors::{esi_request_id, non_json_content_type};
+use crate::models::{access_token_scopes, AuthenticateResponse};
 use crate::pkce::PkceVerifier;
+use crate::util::MAX_CONCURRENT_REQUESTS;
+#[cfg(feature = "universe")]
+use crate::util::MAX_IDS_PER_NAMES_CALL;
 use crate::{groups::*, pkce, prelude::*};
 
 const BASE_URL: &str = "https://esi.evetech.net/";
 const AUTHORIZE_URL: &str = "https://login.eveonline.com/v2/oauth/authorize";
 const TOKEN_URL: &str = "https://login.eveonline.com/v2/oauth/token";
-const SPEC_URL_START: &str = "https://esi.evetech.net/_";
-const SPEC_URL_END: &str = "/swagger.json";
-
-/// Response from SSO when exchanging a SSO code for tokens.
-#[derive(Debug, Deserialize)]
-struct AuthenticateResponse {
-    access_token: String,
-    expires_in: u64,
-    refresh_token: Option<String>,
-}
-
-/// Response from SSO when exchanging a refresh token for access token.
-#[derive(Debug, Deserialize)]
-struct RefreshTokenAuthenticateResponse {
-    access_token: String,
-    expires_in: u64,
-    refresh_token: String,
-}
+const SPEC_URL_PATTERN: &str = "https://esi.evetech.net/_{version}/swagger.json";
+const STATUS_PATH: &str = "latest/status/";
 
 /// Which base URL to start with - the public URL for unauthenticated
 /// calls, or the authenticated URL for making calls to endpoints that
 /// require an access token.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RequestType {
     /// Endpoints that do not require authentication
     Public,
@@ -52,29 +47,88 @@ pub enum RequestType {
     Authenticated,
 }
 
-/// AuthenticationInformation contains data needed to complete the requested authentication flow.
-pub struct AuthenticationInformation {
-    /// URL to call/pass to users to initiate an authentication and get an auth code from ESI.
-    pub authorization_url: String,
-    /// If the default feature "random_state" is enabled, the returned state field string will be
-    /// random; otherwise it'll be "rfesi_unused". The ESI docs link to
-    /// [this auth0 page](https://auth0.com/docs/secure/attack-protection/state-parameters)
-    /// to explain. You need to check the state yourself when the response from ESI is received.
-    pub state: String,
-    /// Filled if you've selected PKCE authentication for application.
-    /// You will need it to authenticate using the code received from ESI.
-    pub pkce_verifier: Option<PkceVerifier>,
+/// Body of a request to ESI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestBody<'a> {
+    /// A JSON body, as used by nearly every endpoint that takes one.
+    Json(&'a str),
+    /// An `application/x-www-form-urlencoded` body.
+    Form(&'a [(&'a str, &'a str)]),
+}
+
+impl RequestBody<'_> {
+    /// Set this as the body of a request.
+    fn apply(self, req_builder: RequestBuilder) -> RequestBuilder {
+        match self {
+            RequestBody::Json(body) => req_builder.body(body.to_owned()),
+            RequestBody::Form(fields) => req_builder.form(fields),
+        }
+    }
+}
+
+/// A response from ESI, with its headers and timing, from [`Esi::query_timed`].
+#[derive(Debug, Clone)]
+pub struct EsiResponse<T> {
+    /// The deserialized response body.
+    pub body: T,
+    /// The response headers.
+    pub headers: HeaderMap,
+    /// How long ESI took to respond, up to receiving the headers.
+    pub elapsed: Duration,
+}
+
+/// One page of results from a paginated endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paged<T> {
+    /// Results on this page.
+    pub items: Vec<T>,
+    /// The page number, starting at 1.
+    pub page: i32,
+    /// Total number of pages, as reported by the `X-Pages` response header.
+    pub total_pages: u32,
+}
+
+impl<T> Paged<T> {
+    /// Get whether there are pages after this one.
+    pub fn has_next(&self) -> bool {
+        i64::from(self.page) < i64::from(self.total_pages)
+    }
+}
+
+/// A user's tokens, along with the pre-built `Authorization` header
+/// value for the access token.
+#[derive(Debug, Default)]
+pub(crate) struct TokenState {
+    auth: EsiAuthState,
+    bearer_header: Option<HeaderValue>,
+}
+
+impl TokenState {
+    fn new(auth: EsiAuthState) -> Self {
+        let bearer_header = auth
+            .access_token
+            .as_deref()
+            .and_then(|at| bearer_header(at).ok());
+        TokenState {
+            auth,
+            bearer_header,
+        }
+    }
 }
 
 /// Struct to interact with ESI.
 ///
 /// Construct an instance of this struct using [`EsiBuilder`](./struct.EsiBuilder.html).
 ///
+/// The user's tokens are held behind a lock, so authenticating and
+/// refreshing only need `&self`, and clones of this struct share them:
+/// a refresh through one clone is seen by all of them. Use
+/// [`Esi::fork_for_user`] for a copy with its own tokens.
+///
 /// # Example
 /// ```rust,no_run
 /// use rfesi::prelude::EsiBuilder;
-/// // the struct must be mutable for some functionality
-/// let mut esi = EsiBuilder::new()
+/// let esi = EsiBuilder::new()
 ///     .user_agent("some user agent")
 ///     .client_id("your_client_id")
 ///     .client_secret("your_client_secret")
@@ -82,7 +136,7 @@ pub struct AuthenticationInformation {
 ///     .build()
 ///     .unwrap();
 /// ```
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Esi {
     pub(crate) version: String,
     pub(crate) client_id: Option<String>,
@@ -94,15 +148,75 @@ pub struct Esi {
     pub(crate) spec_url: String,
     pub(crate) scope: String,
     pub(crate) application_auth: bool,
-    /// The access token from ESI, if set.
-    pub access_token: Option<String>,
-    /// The millisecond unix timestamp after which the access token expires, if present.
-    pub access_expiration: Option<i64>,
-    /// The refresh token from ESI, if set.
-    pub refresh_token: Option<String>,
+    pub(crate) datasource: Option<&'static str>,
+    /// The user's tokens, shared between clones of this struct.
+    pub(crate) tokens: Arc<RwLock<TokenState>>,
     /// HTTP client
     pub(crate) client: Client,
-    pub(crate) spec: Option<Value>,
+    /// The Swagger spec, shared between forks of this struct.
+    pub(crate) spec: Option<Arc<Value>>,
+    /// Lookup of `operationId` to URL path, built from the spec.
+    pub(crate) op_id_map: Option<Arc<HashMap<String, String>>>,
+    /// Lookup of URL path templates back to their `operationId`, built
+    /// alongside `op_id_map` and from the static paths, if enabled.
+    pub(crate) path_index: Arc<PathIndex>,
+    pub(crate) warn_on_deprecated: bool,
+    pub(crate) log_http_errors: bool,
+    /// Error statuses that are not logged, even with `log_http_errors` on.
+    pub(crate) suppressed_log_statuses: HashSet<u16>,
+    /// Source of the current time, if not the system time.
+    pub(crate) clock: Option<Clock>,
+    /// Endpoints that have already been warned about as deprecated.
+    pub(crate) warned_deprecated: Arc<Mutex<HashSet<String>>>,
+    /// How long to cache responses for, by `operationId`.
+    pub(crate) cached_ops: HashMap<String, Duration>,
+    /// Cached responses, keyed by path and query.
+    pub(crate) response_cache: Arc<Mutex<HashMap<String, CachedResponse>>>,
+    /// Displayed security status of systems looked up for route planning.
+    #[cfg(feature = "routes")]
+    pub(crate) system_security: Arc<Mutex<HashMap<i32, f64>>>,
+    /// Insurance prices for every ship type, and when they were fetched.
+    #[cfg(feature = "insurance")]
+    pub(crate) insurance_prices: InsurancePriceCache,
+}
+
+/// Leaves out the client secret and the user's tokens, so that the
+/// struct is safe to log, along with the spec and caches.
+impl fmt::Debug for Esi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let redact = |value: Option<&String>| value.map(|_| "***");
+        let tokens = self.read_tokens();
+        f.debug_struct("Esi")
+            .field("version", &self.version)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redact(self.client_secret.as_ref()))
+            .field("callback_url", &self.callback_url)
+            .field("base_api_url", &self.base_api_url)
+            .field("authorize_url", &self.authorize_url)
+            .field("token_url", &self.token_url)
+            .field("spec_url", &self.spec_url)
+            .field("scope", &self.scope)
+            .field("application_auth", &self.application_auth)
+            .field("datasource", &self.datasource)
+            .field("access_token", &redact(tokens.auth.access_token.as_ref()))
+            .field("access_expiration", &tokens.auth.access_expiration)
+            .field("refresh_token", &redact(tokens.auth.refresh_token.as_ref()))
+            .field("warn_on_deprecated", &self.warn_on_deprecated)
+            .field("log_http_errors", &self.log_http_errors)
+            .field("suppressed_log_statuses", &self.suppressed_log_statuses)
+            .field("cached_ops", &self.cached_ops)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A response body held in the cache for an endpoint set with
+/// [`EsiBuilder::cache_op`].
+#[derive(Debug)]
+pub(crate) struct CachedResponse {
+    /// When the entry expires, in milliseconds since the Unix epoch.
+    expires: i64,
+    body: Vec<u8>,
+    headers: HeaderMap,
 }
 
 impl Esi {
@@ -110,6 +224,12 @@ impl Esi {
     pub(crate) fn from_builder(builder: EsiBuilder) -> EsiResult<Self> {
         let client = builder.construct_client()?;
         let version = builder.version.unwrap_or_else(|| "latest".to_owned());
+        let op_id_map = builder
+            .spec
+            .as_ref()
+            .and_then(|spec| build_op_id_map(spec).ok())
+            .map(Arc::new);
+        let path_index = Arc::new(build_path_index(op_id_map.as_deref()));
         let e = Esi {
             version: version.clone(),
             client_id: builder.client_id,
@@ -118,16 +238,45 @@ impl Esi {
             base_api_url: builder.base_api_url.unwrap_or(BASE_URL.to_string()),
             authorize_url: builder.authorize_url.unwrap_or(AUTHORIZE_URL.to_string()),
             token_url: builder.token_url.unwrap_or(TOKEN_URL.to_string()),
-            spec_url: builder
-                .spec_url
-                .unwrap_or(format!("{}{}{}", SPEC_URL_START, version, SPEC_URL_END)),
+            spec_url: builder.spec_url.unwrap_or_else(|| {
+                builder
+                    .spec_url_pattern
+                    .as_deref()
+                    .unwrap_or(SPEC_URL_PATTERN)
+                    .replace("{version}", &version)
+            }),
             scope: builder.scope.unwrap_or_else(|| "".to_owned()),
             application_auth: builder.application_auth.unwrap_or(false),
-            access_token: builder.access_token,
-            access_expiration: builder.access_expiration,
-            refresh_token: builder.refresh_token,
+            datasource: builder.environment.map(|e| e.datasource()),
+            tokens: Arc::new(RwLock::new(TokenState::new(EsiAuthState {
+                scopes: builder
+                    .access_token
+                    .as_deref()
+                    .and_then(|token| access_token_scopes(token).ok())
+                    .unwrap_or_default(),
+                access_token: builder.access_token,
+                access_expiration: builder.access_expiration,
+                refresh_token: builder.refresh_token,
+            }))),
             client,
+            op_id_map,
+            path_index,
             spec: builder.spec,
+            warn_on_deprecated: builder.warn_on_deprecated.unwrap_or(true),
+            log_http_errors: builder.log_http_errors.unwrap_or(true),
+            suppressed_log_statuses: builder
+                .suppressed_log_statuses
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            clock: builder.clock,
+            warned_deprecated: Arc::new(Mutex::new(HashSet::new())),
+            cached_ops: builder.cached_ops.unwrap_or_default(),
+            response_cache: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "routes")]
+            system_security: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "insurance")]
+            insurance_prices: Arc::new(Mutex::new(None)),
         };
         Ok(e)
     }
@@ -141,6 +290,12 @@ impl Esi {
     /// long time). When using `get_endpoint_for_op_id` however,
     /// you are responsible for calling this function beforehand.
     ///
+    /// With the "vendored_paths" feature enabled, a failure to fetch the
+    /// spec is logged and the paths compiled into the crate are used
+    /// instead. These cover the wrapped endpoints only, so `get_spec`
+    /// will still return `None` and raw lookups of other `operationId`s
+    /// will fail.
+    ///
     /// # Example
     /// ```rust,no_run
     /// # async fn run() {
@@ -154,16 +309,43 @@ impl Esi {
     /// #     .unwrap();
     /// esi.update_spec().await.unwrap();
     /// # }
+    /// ```
     pub async fn update_spec(&mut self) -> EsiResult<()> {
         debug!("Updating spec with version {}", self.version);
+        match self.fetch_spec().await {
+            Ok(data) => {
+                self.op_id_map = build_op_id_map(&data).ok().map(Arc::new);
+                self.path_index = Arc::new(build_path_index(self.op_id_map.as_deref()));
+                self.spec = Some(Arc::new(data));
+                Ok(())
+            }
+            #[cfg(feature = "vendored_paths")]
+            Err(e) => {
+                warn!("Could not fetch spec ({e}); falling back to vendored paths");
+                self.op_id_map = Some(Arc::new(crate::vendored::vendored_op_id_map()));
+                self.path_index = Arc::new(build_path_index(self.op_id_map.as_deref()));
+                Ok(())
+            }
+            #[cfg(not(feature = "vendored_paths"))]
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Check whether a failed response with this status should be logged.
+    fn should_log_status(&self, status: reqwest::StatusCode) -> bool {
+        self.log_http_errors && !self.suppressed_log_statuses.contains(&status.as_u16())
+    }
+
+    /// Download the Swagger spec from ESI.
+    async fn fetch_spec(&self) -> EsiResult<Value> {
         let resp = self.client.get(&self.spec_url).send().await?;
         if !resp.status().is_success() {
-            error!("Got status {} when requesting spec", resp.status());
-            return Err(EsiError::InvalidStatusCode(resp.status().as_u16()));
+            if self.should_log_status(resp.status()) {
+                error!("Got status {} when requesting spec", resp.status());
+            }
+            return Err(EsiError::from_response(&resp));
         }
-        let data: Value = resp.json().await?;
-        self.spec = Some(data);
-        Ok(())
+        Ok(resp.json().await?)
     }
 
     /// Ensure the user has specified all required EVE Developer App information.
@@ -197,7 +379,7 @@ impl Esi {
     /// # Example
     /// ```rust,no_run
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .client_id("your_client_id")
     /// #     .client_secret("your_client_secret")
@@ -252,12 +434,12 @@ impl Esi {
     fn get_auth_headers(&self) -> EsiResult<HeaderMap> {
         self.check_client_info()?;
         let mut map = HeaderMap::new();
-        if self.client_secret.is_some() {
+        if let Some(client_secret) = &self.client_secret {
             let value = base64
                 .encode(format!(
                     "{}:{}",
                     self.client_id.as_ref().unwrap(),
-                    self.client_secret.as_ref().unwrap()
+                    client_secret
                 ))
                 .replace(['\n', ' '], "");
             map.insert(
@@ -265,18 +447,29 @@ impl Esi {
                 HeaderValue::from_str(&format!("Basic {value}"))?,
             );
         }
-        map.insert(
-            header::HOST,
-            HeaderValue::from_static("login.eveonline.com"),
-        );
+        map.insert(header::HOST, HeaderValue::from_str(&self.login_host()?)?);
         Ok(map)
     }
 
+    /// Parse the token URL, which decides the SSO server that's used,
+    /// such as the test server's when the struct is pointed at it.
+    fn token_url(&self) -> EsiResult<reqwest::Url> {
+        reqwest::Url::parse(&self.token_url)
+            .ok()
+            .filter(|url| url.has_host())
+            .ok_or_else(|| EsiError::InvalidTokenUrl(self.token_url.clone()))
+    }
+
+    /// Get the host (and port, if not the default) of the SSO server.
+    fn login_host(&self) -> EsiResult<String> {
+        Ok(login_host(&self.token_url()?))
+    }
+
     /// Authenticate with ESI, exchanging a code from the authorize flow
     /// for an access token that is used to make authenticated calls to ESI.
     ///
-    /// Note that this is one of the functions that requires the struct be
-    /// mutable, as the struct mutates to include the resulting access token.
+    /// The resulting tokens are stored behind the struct's lock, so this
+    /// only needs `&self` and every clone of the struct sees them.
     ///
     /// If the "validate_jwt" feature is enabled (by default), then the access
     /// token's claims will be returned. If the feature is not enabled, then
@@ -286,7 +479,7 @@ impl Esi {
     /// ```rust,no_run
     /// # async fn run() {
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .client_id("your_client_id")
     /// #     .client_secret("your_client_secret")
@@ -301,7 +494,7 @@ impl Esi {
     /// ```rust,no_run
     /// # use rfesi::prelude::*;
     ///  async fn run() {
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .client_id("your_client_id")
     /// #     .callback_url("your_callback_url")
@@ -313,7 +506,7 @@ impl Esi {
     /// # }
     /// ```
     pub async fn authenticate(
-        &mut self,
+        &self,
         code: &str,
         pkce_verifier: Option<PkceVerifier>,
     ) -> EsiResult<Option<TokenClaims>> {
@@ -333,13 +526,16 @@ impl Esi {
             .send()
             .await?;
         if resp.status() != 200 {
-            warn!(
-                "Got status {} when making call to authenticate",
-                resp.status()
-            );
-            return Err(EsiError::InvalidStatusCode(resp.status().as_u16()));
+            if self.should_log_status(resp.status()) {
+                warn!(
+                    "Got status {} when making call to authenticate",
+                    resp.status()
+                );
+            }
+            return Err(EsiError::from_sso_response(resp).await);
         }
         let data: AuthenticateResponse = resp.json().await?;
+        data.token_type.ensure_bearer()?;
         #[allow(unused_variables)]
         let claim_data: Option<TokenClaims> = None;
         #[cfg(feature = "validate_jwt")]
@@ -348,13 +544,25 @@ impl Esi {
                 &self.client,
                 &data.access_token,
                 self.client_id.as_ref().unwrap(),
+                &self.token_url()?,
             )
             .await?,
         );
-        self.access_token = Some(data.access_token);
-        // the response's "expires_in" field is seconds but need millis
-        self.access_expiration = Some((data.expires_in as i64 * 1_000) + current_time_millis()?);
-        self.refresh_token = data.refresh_token;
+        bearer_header(&data.access_token)?;
+        let scopes = match &claim_data {
+            Some(claims) => claims.scopes(),
+            // the tokens are stored even if the scopes can't be read
+            None => access_token_scopes(&data.access_token)
+                .ok()
+                .unwrap_or_default(),
+        };
+        self.set_auth_state(EsiAuthState {
+            access_token: Some(data.access_token),
+            // the response's "expires_in" field is seconds but need millis
+            access_expiration: Some((data.expires_in as i64 * 1_000) + self.now_millis()?),
+            refresh_token: data.refresh_token,
+            scopes,
+        });
         Ok(claim_data)
     }
 
@@ -370,7 +578,7 @@ impl Esi {
     /// ```rust,no_run
     /// # async fn run() {
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .client_id("your_client_id")
     /// #     .client_secret("your_client_secret")
@@ -380,7 +588,7 @@ impl Esi {
     /// esi.use_refresh_token("abcdef...").await.unwrap();
     /// # }
     /// ```
-    pub async fn use_refresh_token(&mut self, refresh_token: &str) -> EsiResult<()> {
+    pub async fn use_refresh_token(&self, refresh_token: &str) -> EsiResult<()> {
         self.refresh_access_token(Some(refresh_token)).await?;
         Ok(())
     }
@@ -397,7 +605,7 @@ impl Esi {
     /// ```rust,no_run
     /// # async fn run() {
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .refresh_token(Some("MyRefreshToken"))
     /// #     .build()
@@ -409,17 +617,17 @@ impl Esi {
     /// ```rust,no_run
     /// # async fn run() {
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .build()
     /// #     .unwrap();
     /// esi.refresh_access_token(Some("MyRefreshToken")).await.unwrap();
     /// # }
     /// ```
-    pub async fn refresh_access_token(&mut self, refresh_token: Option<&str>) -> EsiResult<()> {
+    pub async fn refresh_access_token(&self, refresh_token: Option<&str>) -> EsiResult<()> {
         let token = if let Some(token) = refresh_token {
             token.to_string()
-        } else if let Some(token) = self.refresh_token.clone() {
+        } else if let Some(token) = self.refresh_token() {
             token
         } else {
             return Err(EsiError::NoRefreshTokenAvailable);
@@ -439,17 +647,179 @@ impl Esi {
             .send()
             .await?;
         if resp.status() != 200 {
-            warn!(
-                "Got status {} when making call to authenticate via a refresh token",
-                resp.status()
-            );
-            return Err(EsiError::InvalidStatusCode(resp.status().as_u16()));
+            if self.should_log_status(resp.status()) {
+                warn!(
+                    "Got status {} when making call to authenticate via a refresh token",
+                    resp.status()
+                );
+            }
+            return Err(EsiError::from_sso_response(resp).await);
+        }
+        let data: AuthenticateResponse = resp.json().await?;
+        data.token_type.ensure_bearer()?;
+        bearer_header(&data.access_token)?;
+        // the tokens are stored even if the scopes can't be read
+        let scopes = access_token_scopes(&data.access_token)
+            .ok()
+            .unwrap_or_default();
+        self.set_auth_state(EsiAuthState {
+            access_token: Some(data.access_token),
+            // the response's "expires_in" field is seconds, need millis
+            access_expiration: Some((data.expires_in as i64 * 1_000) + self.now_millis()?),
+            // SSO always returns a refresh token here, but keep the
+            // one that was used if it doesn't
+            refresh_token: data.refresh_token.or(Some(token)),
+            scopes,
+        });
+        Ok(())
+    }
+
+    /// Get a copy of this struct for another user, with that user's tokens.
+    ///
+    /// Everything that isn't specific to a user is shared with this struct
+    /// rather than copied: the HTTP client and its connection pool, the
+    /// spec and its operationId lookup, and the caches of public data.
+    /// This makes forking cheap enough to do per request in a service
+    /// that acts on behalf of many characters.
+    ///
+    /// The fork's tokens are independent of this struct's, so refreshing
+    /// one doesn't affect the other; use [`Esi::auth_state`] to read back
+    /// a fork's tokens after a refresh.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let user_esi = esi.fork_for_user(EsiAuthState {
+    ///     access_token: Some("abc...".to_owned()),
+    ///     access_expiration: Some(1_700_000_000_000),
+    ///     refresh_token: Some("def...".to_owned()),
+    ///     scopes: vec!["esi-skills.read_skills.v1".to_owned()],
+    /// });
+    /// ```
+    pub fn fork_for_user(&self, auth: EsiAuthState) -> Esi {
+        Esi {
+            tokens: Arc::new(RwLock::new(TokenState::new(auth))),
+            ..self.clone()
+        }
+    }
+
+    /// Read the token state, ignoring poisoning as it's always left consistent.
+    fn read_tokens(&self) -> RwLockReadGuard<'_, TokenState> {
+        self.tokens.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get a copy of this struct's tokens.
+    pub fn auth_state(&self) -> EsiAuthState {
+        self.read_tokens().auth.clone()
+    }
+
+    /// Replace this struct's tokens, and those of its clones.
+    pub fn set_auth_state(&self, auth: EsiAuthState) {
+        let state = TokenState::new(auth);
+        *self.tokens.write().unwrap_or_else(PoisonError::into_inner) = state;
+    }
+
+    /// Get the access token from ESI, if set.
+    pub fn access_token(&self) -> Option<String> {
+        self.read_tokens().auth.access_token.clone()
+    }
+
+    /// Get the millisecond unix timestamp after which the access token
+    /// expires, if present.
+    pub fn access_expiration(&self) -> Option<i64> {
+        self.read_tokens().auth.access_expiration
+    }
+
+    /// Get the refresh token from ESI, if set.
+    pub fn refresh_token(&self) -> Option<String> {
+        self.read_tokens().auth.refresh_token.clone()
+    }
+
+    /// Get the scopes that the user granted to the access token.
+    ///
+    /// Set by [`Esi::authenticate`] and [`Esi::refresh_access_token`],
+    /// whether or not the "validate_jwt" feature is enabled. Persist these
+    /// with the rest of the [`EsiAuthState`] to know what the user has
+    /// already granted without prompting them again.
+    pub fn granted_scopes(&self) -> Vec<String> {
+        self.read_tokens().auth.scopes.clone()
+    }
+
+    /// Check whether authenticated requests can be made right now:
+    /// an access token is present and has not expired.
+    pub fn is_authenticated(&self) -> bool {
+        let tokens = self.read_tokens();
+        match (&tokens.auth.access_token, tokens.auth.access_expiration) {
+            (Some(_), Some(expiration)) => self.now_millis().is_ok_and(|now| expiration >= now),
+            _ => false,
+        }
+    }
+
+    /// Get the current time in milliseconds since the Unix epoch, from
+    /// the clock set with [`EsiBuilder::clock`] or the system time.
+    pub(crate) fn now_millis(&self) -> EsiResult<i64> {
+        match self.clock {
+            Some(Clock(clock)) => Ok(clock()),
+            None => current_time_millis(),
         }
-        let data: RefreshTokenAuthenticateResponse = resp.json().await?;
-        self.access_token = Some(data.access_token);
-        // the response's "expires_in" field is seconds, need millis
-        self.access_expiration = Some((data.expires_in as i64 * 1_000) + current_time_millis()?);
-        self.refresh_token = Some(data.refresh_token);
+    }
+
+    /// Get the current time from the clock set with [`EsiBuilder::clock`],
+    /// or the system time.
+    ///
+    /// Pass this to the helpers on response structs that compare against
+    /// the current time, such as `MemberTracking::likely_inactive`, so
+    /// that they use the same clock as this struct.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use rfesi::prelude::*;
+    /// let esi = EsiBuilder::new()
+    ///     .user_agent("some user agent")
+    ///     .clock(|| 1_700_000_000_000)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(esi.now().unwrap().timestamp(), 1_700_000_000);
+    /// ```
+    pub fn now(&self) -> EsiResult<DateTime<Utc>> {
+        let millis = self.now_millis()?;
+        DateTime::from_timestamp_millis(millis).ok_or(EsiError::InvalidClockTime(millis))
+    }
+
+    /// Check whether a refresh token is present, so that a new
+    /// access token can be requested with `refresh_access_token`.
+    pub fn is_refreshable(&self) -> bool {
+        self.read_tokens().auth.refresh_token.is_some()
+    }
+
+    /// Check that ESI can be reached and is responding.
+    ///
+    /// Makes a request to the server status endpoint, which doesn't need
+    /// the spec or an access token. An error from ESI is returned as-is,
+    /// so a 420 means that this app is being error limited and a 503
+    /// means that ESI or the EVE server is down.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// if let Err(e) = esi.ping().await {
+    ///     eprintln!("ESI is unavailable: {e}");
+    /// }
+    /// # }
+    /// ```
+    pub async fn ping(&self) -> EsiResult<()> {
+        let _: Value = self
+            .query("GET", RequestType::Public, STATUS_PATH, None, None)
+            .await?;
         Ok(())
     }
 
@@ -471,7 +841,7 @@ impl Esi {
     /// # async fn run() {
     /// # use serde::Deserialize;
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
     /// #     .client_id("your_client_id")
     /// #     .client_secret("your_client_secret")
@@ -491,306 +861,1158 @@ impl Esi {
         query: Option<&[(&str, &str)]>,
         body: Option<&str>,
     ) -> EsiResult<T> {
-        debug!(
-            "Making {:?} {} request to {} with query: {:?}",
-            request_type, method, endpoint, query
-        );
-        if request_type == RequestType::Authenticated {
-            if self.access_token.is_none() {
-                return Err(EsiError::MissingAuthentication);
-            }
-            if self.access_expiration.unwrap() < current_time_millis()? {
-                return Err(EsiError::AccessTokenExpired);
-            }
-        }
-        let headers = {
-            let mut map = HeaderMap::new();
-            // The 'user-agent' and 'content-type' headers are set in the default headers
-            // from the builder, so all that's required here is to set the authorization
-            // header, if present.
-            if let Some(at) = &self.access_token {
-                map.insert(
-                    header::AUTHORIZATION,
-                    HeaderValue::from_str(&format!("Bearer {at}"))?,
-                );
-            }
-            map
-        };
-        let url = format!("{}{endpoint}", self.base_api_url);
-        let mut req_builder = self
-            .client
-            .request(Method::from_str(method)?, &url)
-            .headers(headers)
-            .query(query.unwrap_or(&[]));
-        req_builder = match body {
-            Some(b) => req_builder.body(b.to_owned()),
-            None => req_builder,
-        };
-        let req = req_builder.build()?;
-        let resp = self.client.execute(req).await?;
-        if !resp.status().is_success() {
-            return Err(EsiError::InvalidStatusCode(resp.status().as_u16()));
-        }
-        let text = resp.text().await?;
-        let data: T = serde_json::from_str(&text)?;
-        Ok(data)
+        self.query_with_body(
+            method,
+            request_type,
+            endpoint,
+            query,
+            body.map(RequestBody::Json),
+        )
+        .await
     }
 
-    /// Resolve an `operationId` to a URL path utilizing the Swagger spec.
-    ///
-    /// If the spec has not yet been retrieved when calling this function,
-    /// an API call will be made to ESI to fetch that data (thus the
-    /// async signature of this function). If you don't need that help (by
-    /// explicitly making a call to `update_spec` prior) then you can use
-    /// the `get_endpoint_for_op_id` function, which is synchronous.
-    ///
-    /// Note that when making use of this function along with `query`, you
-    /// are responsible for resolving any/all URL parameters that the endpoint
-    /// may contain.
+    /// Make a request to ESI, like [`Esi::query`], with either a JSON
+    /// or a form-encoded body.
     ///
     /// # Example
     /// ```rust,no_run
     /// # async fn run() {
     /// # use rfesi::prelude::*;
-    /// # let mut esi = EsiBuilder::new()
+    /// # let esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
-    /// #     .client_id("your_client_id")
-    /// #     .client_secret("your_client_secret")
-    /// #     .callback_url("your_callback_url")
     /// #     .build()
     /// #     .unwrap();
-    /// let endpoint = esi
-    ///     .try_get_endpoint_for_op_id("get_alliances_alliance_id_contacts_labels")
+    /// let data: serde_json::Value = esi
+    ///     .query_with_body(
+    ///         "POST",
+    ///         RequestType::Public,
+    ///         "abc",
+    ///         None,
+    ///         Some(RequestBody::Form(&[("key", "value")])),
+    ///     )
     ///     .await
     ///     .unwrap();
     /// # }
     /// ```
-    pub async fn try_get_endpoint_for_op_id(&mut self, op_id: &str) -> EsiResult<String> {
-        if self.spec.is_none() {
-            debug!("Spec is `None`; must fetch before looking up op_id");
-            self.update_spec().await?;
-        }
-        self.get_endpoint_for_op_id(op_id)
+    pub async fn query_with_body<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<RequestBody<'_>>,
+    ) -> EsiResult<T> {
+        let (data, _) = self
+            .query_with_headers(method, request_type, endpoint, query, body)
+            .await?;
+        Ok(data)
     }
 
-    /// Resolve an `operationId` to a URL path utilizing the Swagger spec.
+    /// Make a request to the endpoint for an `operationId`.
     ///
-    /// If the spec has not yet been retrieved when calling this function,
-    /// this function will return an error.
+    /// The HTTP method, and whether the endpoint requires authentication,
+    /// are looked up in the spec, and the endpoint's path parameters are
+    /// filled in from `path_params`. This is the simplest way to call an
+    /// endpoint that doesn't have a wrapper function.
     ///
-    /// Note that when making use of this function along with `query`, you
-    /// are responsible for resolving any/all URL parameters that the endpoint
-    /// may contain.
+    /// Fails if the spec has not been retrieved, or if any of the
+    /// endpoint's path parameters are missing from `path_params`.
     ///
     /// # Example
     /// ```rust,no_run
+    /// # async fn run() {
     /// # use rfesi::prelude::*;
     /// # let mut esi = EsiBuilder::new()
     /// #     .user_agent("some user agent")
-    /// #     .client_id("your_client_id")
-    /// #     .client_secret("your_client_secret")
-    /// #     .callback_url("your_callback_url")
     /// #     .build()
     /// #     .unwrap();
-    /// let endpoint = esi.get_endpoint_for_op_id("get_alliances_alliance_id_contacts_labels").unwrap();
+    /// # esi.update_spec().await.unwrap();
+    /// let labels: serde_json::Value = esi
+    ///     .call(
+    ///         "get_alliances_alliance_id_contacts_labels",
+    ///         &[("alliance_id", "99000001")],
+    ///         &[],
+    ///         None,
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// # }
     /// ```
-    pub fn get_endpoint_for_op_id(&self, op_id: &str) -> EsiResult<String> {
-        if self.spec.is_none() {
-            return Err(EsiError::EmptySpec);
-        }
-        let data = self
-            .spec
-            .as_ref()
-            .ok_or_else(|| EsiError::FailedSpecParse("Unwrapping JSON Value".to_owned()))?;
-        let paths = data["paths"]
-            .as_object()
-            .ok_or_else(|| EsiError::FailedSpecParse("Getting paths".to_owned()))?;
-        for (path_str, path_obj) in paths.iter() {
-            let path = path_obj
-                .as_object()
-                .ok_or_else(|| EsiError::FailedSpecParse("Parsing a path".to_owned()))?;
-            for method in path.values() {
-                let operation_id = match method["operationId"].as_str() {
-                    Some(o) => o,
-                    None => continue,
-                };
-                if operation_id == op_id {
-                    // the paths contain a leading slash, so strip it
-                    return Ok(path_str.chars().skip(1).collect());
-                }
-            }
-        }
-        Err(EsiError::UnknownOperationID(op_id.to_owned()))
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        op_id: &str,
+        path_params: &[(&str, &str)],
+        query: &[(&str, &str)],
+        body: Option<&str>,
+    ) -> EsiResult<T> {
+        let data = self.spec.as_ref().ok_or(EsiError::EmptySpec)?;
+        let (template, method, request_type) = find_operation(data, op_id)?;
+        let endpoint = fill_path_params(&template, path_params)?;
+        self.query(&method, request_type, &endpoint, Some(query), body)
+            .await
     }
 
-    /// Retrieve this struct's OpenAPI specification.
+    /// Get the SSO scopes that the access token needs to call an endpoint,
+    /// from its `operationId`.
     ///
-    /// Use in tandem with [EsiBuilder::spec].
-    pub fn get_spec(&self) -> Option<&Value> {
-        self.spec.as_ref()
+    /// Public endpoints need no scopes, and return an empty list. Checking
+    /// these against the scopes in [`TokenClaims`] before making a request
+    /// avoids a `Forbidden` error.
+    ///
+    /// Fails if the spec has not been retrieved.
+    pub fn required_scopes_for_op(&self, op_id: &str) -> EsiResult<Vec<String>> {
+        let data = self.spec.as_ref().ok_or(EsiError::EmptySpec)?;
+        let (_, _, op) = find_operation_object(data, op_id)?;
+        Ok(operation_scopes(op))
     }
 
-    /// Call endpoints under the "alliance" group in ESI.
-    pub fn group_alliance(&self) -> AllianceGroup {
-        AllianceGroup { esi: self }
+    /// Make a request to ESI, like [`Esi::query_with_body`], returning
+    /// the response headers alongside the deserialized body.
+    ///
+    /// Useful for reading headers like `X-Pages`, `ETag`, and `Expires`.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let (orders, headers): (serde_json::Value, _) = esi
+    ///     .query_with_headers(
+    ///         "GET",
+    ///         RequestType::Public,
+    ///         "latest/markets/10000002/orders/",
+    ///         None,
+    ///         None,
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// println!("{:?} pages", headers.get("x-pages"));
+    /// # }
+    /// ```
+    pub async fn query_with_headers<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<RequestBody<'_>>,
+    ) -> EsiResult<(T, HeaderMap)> {
+        let response = self
+            .query_timed(method, request_type, endpoint, query, body)
+            .await?;
+        Ok((response.body, response.headers))
     }
 
-    /// Call endpoints under the "Assets" group in ESI.
-    pub fn group_assets(&self) -> AssetsGroup {
-        AssetsGroup { esi: self }
+    /// Make a request to ESI, like [`Esi::query_with_body`], returning
+    /// the response headers and how long ESI took to respond alongside
+    /// the deserialized body.
+    ///
+    /// A response served from the cache has an `elapsed` of zero.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let response: EsiResponse<serde_json::Value> = esi
+    ///     .query_timed("GET", RequestType::Public, "latest/status/", None, None)
+    ///     .await
+    ///     .unwrap();
+    /// println!("ESI took {:?}", response.elapsed);
+    /// # }
+    /// ```
+    pub async fn query_timed<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<RequestBody<'_>>,
+    ) -> EsiResult<EsiResponse<T>> {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            let op_id = self.get_op_id_for_endpoint(method, endpoint);
+            let span = tracing::info_span!(
+                "esi_request",
+                op_id = op_id.as_deref().unwrap_or(endpoint),
+                method,
+                status = tracing::field::Empty,
+            );
+            self.send_request(method, request_type, endpoint, query, body)
+                .instrument(span)
+                .await
+        }
+        #[cfg(not(feature = "tracing"))]
+        self.send_request(method, request_type, endpoint, query, body)
+            .await
     }
 
-    /// Call endpoints under the "Bookmarks" group in ESI.
-    pub fn group_bookmarks(&self) -> BookmarksGroup {
-        BookmarksGroup { esi: self }
-    }
+    /// Make a request to ESI; see [`Esi::query_timed`].
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        body: Option<RequestBody<'_>>,
+    ) -> EsiResult<EsiResponse<T>> {
+        debug!(
+            "Making {:?} {} request to {} with query: {:?}",
+            request_type, method, endpoint, query
+        );
+        let authorization = {
+            let tokens = self.read_tokens();
+            if request_type == RequestType::Authenticated {
+                if tokens.auth.access_token.is_none() {
+                    return Err(EsiError::MissingAuthentication);
+                }
+                let now = self.now_millis()?;
+                if tokens.auth.access_expiration.is_some_and(|exp| exp < now) {
+                    return Err(EsiError::AccessTokenExpired);
+                }
+            }
+            tokens.bearer_header.clone()
+        };
+        // sorted so that the same params in a different order make the same request
+        let mut query = query.unwrap_or(&[]).to_vec();
+        query.sort_unstable();
+        let cache = self
+            .cache_ttl(method, request_type, endpoint)
+            .map(|ttl| (ttl, cache_key(endpoint, &query)));
+        if let Some((_, key)) = &cache {
+            if let Some((body, headers)) = self.cached_response(key) {
+                debug!("Using cached response for {}", endpoint);
+                return Ok(EsiResponse {
+                    body: serde_json::from_slice(&body)?,
+                    headers,
+                    elapsed: Duration::ZERO,
+                });
+            }
+        }
+        let headers = {
+            let mut map = HeaderMap::new();
+            // The 'user-agent' and 'content-type' headers are set in the default headers
+            // from the builder, so all that's required here is to set the authorization
+            // header, if present.
+            if let Some(value) = authorization {
+                map.insert(header::AUTHORIZATION, value);
+            }
+            map
+        };
+        let url = format!("{}{endpoint}", self.base_api_url);
+        let mut req_builder = self
+            .client
+            .request(Method::from_str(method)?, &url)
+            .headers(headers)
+            .query(&query);
+        if let Some(datasource) = self.datasource {
+            req_builder = req_builder.query(&[("datasource", datasource)]);
+        }
+        if let Some(body) = body {
+            req_builder = body.apply(req_builder);
+        }
+        let req = req_builder.build()?;
+        let start = Instant::now();
+        let resp = self.client.execute(req).await?;
+        let elapsed = start.elapsed();
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("status", resp.status().as_u16());
+        if !resp.status().is_success() && self.should_log_status(resp.status()) {
+            let op_id = self.get_op_id_for_endpoint(method, endpoint);
+            warn!(
+                "Got status {} for {} ({})",
+                resp.status().as_u16(),
+                op_id.as_deref().unwrap_or(endpoint),
+                resp.url()
+            );
+        }
+        match resp.status().as_u16() {
+            401 => {
+                return Err(EsiError::Unauthorized {
+                    request_id: esi_request_id(resp.headers()),
+                })
+            }
+            403 => {
+                return Err(EsiError::Forbidden {
+                    request_id: esi_request_id(resp.headers()),
+                })
+            }
+            _ if !resp.status().is_success() => {
+                return Err(EsiError::from_response(&resp));
+            }
+            _ => {}
+        }
+        let headers = resp.headers().clone();
+        if let Some(content_type) = non_json_content_type(&headers) {
+            return Err(EsiError::NonJsonResponse {
+                status: resp.status().as_u16(),
+                content_type,
+                request_id: esi_request_id(&headers),
+            });
+        }
+        if self.warn_on_deprecated {
+            if let Some(warning) = headers.get(header::WARNING) {
+                self.warn_deprecated(method, endpoint, warning);
+            }
+        }
+        let bytes = resp.bytes().await?;
+        // endpoints that return "204 No Content" have no body to parse
+        let bytes: &[u8] = if bytes.is_empty() { b"null" } else { &bytes };
+        let data: T = serde_json::from_slice(bytes)?;
+        if let Some((ttl, key)) = cache {
+            self.cache_response(key, ttl, bytes, &headers);
+        }
+        Ok(EsiResponse {
+            body: data,
+            headers,
+            elapsed,
+        })
+    }
+
+    /// Make a request, caching the response until the time in its
+    /// `Expires` header.
+    ///
+    /// For endpoints whose data changes rarely. Responses without a
+    /// usable `Expires` header are not cached.
+    #[cfg(feature = "market")]
+    pub(crate) async fn query_until_expires<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+    ) -> EsiResult<T> {
+        let mut sorted = query.unwrap_or(&[]).to_vec();
+        sorted.sort_unstable();
+        let key = cache_key(endpoint, &sorted);
+        if let Some((body, _)) = self.cached_response(&key) {
+            debug!("Using cached response for {}", endpoint);
+            return Ok(serde_json::from_slice(&body)?);
+        }
+        let (data, headers): (Value, _) = self
+            .query_with_headers(method, request_type, endpoint, query, None)
+            .await?;
+        if let Some(ttl) = expires_in(&headers, self.now()?) {
+            self.cache_response(key, ttl, &serde_json::to_vec(&data)?, &headers);
+        }
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Get how long to cache responses from an endpoint, if it was
+    /// set with [`EsiBuilder::cache_op`].
+    fn cache_ttl(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+    ) -> Option<Duration> {
+        if self.cached_ops.is_empty() || method != "GET" || request_type != RequestType::Public {
+            return None;
+        }
+        self.cached_ops.iter().find_map(|(op_id, ttl)| {
+            let template = self.get_wrapped_endpoint(op_id).ok()?;
+            path_matches(&template, endpoint).then_some(*ttl)
+        })
+    }
+
+    /// Get an unexpired response from the cache.
+    fn cached_response(&self, key: &str) -> Option<(Vec<u8>, HeaderMap)> {
+        let now = self.now_millis().ok()?;
+        let cache = self.response_cache.lock().ok()?;
+        cache
+            .get(key)
+            .filter(|cached| cached.expires > now)
+            .map(|cached| (cached.body.clone(), cached.headers.clone()))
+    }
+
+    /// Store a response in the cache, dropping any expired entries.
+    fn cache_response(&self, key: String, ttl: Duration, body: &[u8], headers: &HeaderMap) {
+        let Ok(now) = self.now_millis() else {
+            return;
+        };
+        if let Ok(mut cache) = self.response_cache.lock() {
+            cache.retain(|_, cached| cached.expires > now);
+            cache.insert(
+                key,
+                CachedResponse {
+                    expires: now.saturating_add(ttl.as_millis().try_into().unwrap_or(i64::MAX)),
+                    body: body.to_vec(),
+                    headers: headers.clone(),
+                },
+            );
+        }
+    }
+
+    /// Log a response's `warning` header, once per endpoint.
+    fn warn_deprecated(&self, method: &str, endpoint: &str, warning: &HeaderValue) {
+        let key = self
+            .get_op_id_for_endpoint(method, endpoint)
+            .unwrap_or_else(|| endpoint.to_owned());
+        let first = self
+            .warned_deprecated
+            .lock()
+            .map(|mut warned| warned.insert(key.clone()))
+            .unwrap_or(false);
+        if first {
+            warn!(
+                "ESI returned a warning for {}, which may be deprecated: {}",
+                key,
+                warning.to_str().unwrap_or_default()
+            );
+        }
+    }
+
+    /// Make a request to a paginated ESI endpoint, fetching every page
+    /// as reported by the `X-Pages` response header.
+    ///
+    /// The first page is fetched to learn the page count, and then the rest
+    /// are fetched concurrently, with a bounded number of requests in flight.
+    /// Any `page` parameter in `query` is replaced. The items are returned in
+    /// page order; a failure on any page fails the whole call.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let orders: Vec<serde_json::Value> = esi
+    ///     .query_all_pages(
+    ///         "GET",
+    ///         RequestType::Public,
+    ///         "latest/markets/10000002/orders/",
+    ///         Some(&[("order_type", "sell")]),
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn query_all_pages<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+    ) -> EsiResult<Vec<T>> {
+        let first: Paged<T> = self
+            .query_page(method, request_type, endpoint, query, None)
+            .await?;
+        // the page count is at most i32::MAX, so each page fits
+        let rest: Vec<Paged<T>> = stream::iter(2..=first.total_pages as i32)
+            .map(|page| self.query_page(method, request_type, endpoint, query, Some(page)))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        let mut results = first.items;
+        results.extend(rest.into_iter().flat_map(|paged| paged.items));
+        Ok(results)
+    }
+
+    /// Make a request for a single page of a paginated ESI endpoint,
+    /// along with the total page count from the `X-Pages` response header.
+    ///
+    /// The first page is fetched if `page` is `None`. An `X-Pages` header
+    /// that isn't a valid page count is an `InvalidPageCount` error.
+    ///
+    /// Used by the `api_get_paged!` macro.
+    #[doc(hidden)]
+    pub async fn query_page<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+        page: Option<i32>,
+    ) -> EsiResult<Paged<T>> {
+        let page = page.unwrap_or(1);
+        let page_str = page.to_string();
+        let mut params: Vec<(&str, &str)> = query
+            .unwrap_or(&[])
+            .iter()
+            .filter(|(key, _)| *key != "page")
+            .copied()
+            .collect();
+        params.push(("page", &page_str));
+        let (items, headers) = self
+            .query_with_headers(method, request_type, endpoint, Some(&params), None)
+            .await?;
+        Ok(Paged {
+            items,
+            page,
+            total_pages: parse_total_pages(&headers)?,
+        })
+    }
+
+    /// Get the total page count of a paginated ESI endpoint.
+    ///
+    /// The first page is requested and its `X-Pages` response header is
+    /// returned, defaulting to 1 when the header is absent. A header that
+    /// isn't a positive number is an `InvalidPageCount` error. The items on
+    /// the page are not deserialized. Useful for deciding how many page requests
+    /// to make concurrently.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let pages = esi
+    ///     .get_total_pages(RequestType::Public, "latest/markets/10000002/orders/", None)
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_total_pages(
+        &self,
+        request_type: RequestType,
+        endpoint: &str,
+        query: Option<&[(&str, &str)]>,
+    ) -> EsiResult<u32> {
+        let mut params: Vec<(&str, &str)> = query
+            .unwrap_or(&[])
+            .iter()
+            .filter(|(key, _)| *key != "page")
+            .copied()
+            .collect();
+        params.push(("page", "1"));
+        let (_, headers): (IgnoredAny, _) = self
+            .query_with_headers("GET", request_type, endpoint, Some(&params), None)
+            .await?;
+        parse_total_pages(&headers)
+    }
+
+    /// Resolve an `operationId` to a URL path utilizing the Swagger spec.
+    ///
+    /// If the spec has not yet been retrieved when calling this function,
+    /// an API call will be made to ESI to fetch that data (thus the
+    /// async signature of this function). If you don't need that help (by
+    /// explicitly making a call to `update_spec` prior) then you can use
+    /// the `get_endpoint_for_op_id` function, which is synchronous.
+    ///
+    /// Note that when making use of this function along with `query`, you
+    /// are responsible for resolving any/all URL parameters that the endpoint
+    /// may contain.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let mut esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .client_id("your_client_id")
+    /// #     .client_secret("your_client_secret")
+    /// #     .callback_url("your_callback_url")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let endpoint = esi
+    ///     .try_get_endpoint_for_op_id("get_alliances_alliance_id_contacts_labels")
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn try_get_endpoint_for_op_id(&mut self, op_id: &str) -> EsiResult<String> {
+        if self.spec.is_none() && self.op_id_map.is_none() {
+            debug!("Spec is `None`; must fetch before looking up op_id");
+            self.update_spec().await?;
+        }
+        self.get_endpoint_for_op_id(op_id)
+    }
+
+    /// Resolve an `operationId` to a URL path utilizing the Swagger spec.
+    ///
+    /// If the spec has not yet been retrieved when calling this function,
+    /// this function will return an error.
+    ///
+    /// Note that when making use of this function along with `query`, you
+    /// are responsible for resolving any/all URL parameters that the endpoint
+    /// may contain.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .client_id("your_client_id")
+    /// #     .client_secret("your_client_secret")
+    /// #     .callback_url("your_callback_url")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let endpoint = esi.get_endpoint_for_op_id("get_alliances_alliance_id_contacts_labels").unwrap();
+    /// ```
+    pub fn get_endpoint_for_op_id(&self, op_id: &str) -> EsiResult<String> {
+        self.lookup_endpoint(op_id).map(Cow::into_owned)
+    }
+
+    /// Resolve an `operationId` to a URL path, borrowing it from the
+    /// `operationId` lookup rather than copying it where possible.
+    fn lookup_endpoint(&self, op_id: &str) -> EsiResult<Cow<'_, str>> {
+        let unknown = || EsiError::UnknownOperationID(op_id.to_owned());
+        match (&self.op_id_map, &self.spec) {
+            (Some(map), _) => map
+                .get(op_id)
+                .map(|path| Cow::Borrowed(path.as_str()))
+                .ok_or_else(unknown),
+            // only happens if the spec is malformed, so this surfaces the parse error
+            (None, Some(data)) => build_op_id_map(data)?
+                .remove(op_id)
+                .map(Cow::Owned)
+                .ok_or_else(unknown),
+            (None, None) => Err(EsiError::EmptySpec),
+        }
+    }
+
+    /// Resolve the `operationId` of a wrapped endpoint to a URL path.
+    ///
+    /// Used by the `api_get!` and `api_post!` macros. With the "static_paths"
+    /// feature enabled, the paths compiled into the crate are used and the
+    /// spec is only consulted for `operationId`s that aren't among them.
+    /// Otherwise, this is the same as `get_endpoint_for_op_id`.
+    ///
+    /// The path is borrowed rather than copied where possible, as endpoints
+    /// without path parameters are used as-is.
+    #[doc(hidden)]
+    pub fn get_wrapped_endpoint(&self, op_id: &str) -> EsiResult<Cow<'_, str>> {
+        #[cfg(feature = "static_paths")]
+        if let Some(path) = crate::vendored::vendored_path(op_id) {
+            return Ok(Cow::Borrowed(path));
+        }
+        self.lookup_endpoint(op_id)
+    }
+
+    /// Resolve several `operationId`s to URL paths at once.
+    ///
+    /// Useful for helpers that call a number of endpoints. Fails
+    /// if any of the `operationId`s cannot be resolved.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let paths = esi
+    ///     .resolve_many(&["get_characters_character_id", "get_characters_character_id_portrait"])
+    ///     .unwrap();
+    /// ```
+    pub fn resolve_many<'a>(&self, op_ids: &[&'a str]) -> EsiResult<HashMap<&'a str, String>> {
+        op_ids
+            .iter()
+            .map(|op_id| Ok((*op_id, self.get_endpoint_for_op_id(op_id)?)))
+            .collect()
+    }
+
+    /// Resolve a set of IDs to their names and categories.
+    ///
+    /// Works for characters, corporations, alliances, factions, solar
+    /// systems, constellations, regions, stations, and types. Duplicate
+    /// IDs are only looked up once, and the lookups are chunked to stay
+    /// within ESI's per-call limit.
+    ///
+    /// Note that ESI fails the whole call if any ID in a chunk is invalid.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # async fn run() {
+    /// # use rfesi::prelude::*;
+    /// # let esi = EsiBuilder::new()
+    /// #     .user_agent("some user agent")
+    /// #     .build()
+    /// #     .unwrap();
+    /// let names = esi.resolve_names(&[30000142, 2114794365]).await.unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "universe")]
+    pub async fn resolve_names(&self, ids: &[i64]) -> EsiResult<HashMap<i64, ResolvedName>> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        let mut resolved = HashMap::with_capacity(ids.len());
+        for chunk in ids.chunks(MAX_IDS_PER_NAMES_CALL) {
+            let names = self.group_universe().get_names(chunk).await?;
+            resolved.extend(names.into_iter().map(|n| {
+                (
+                    n.id,
+                    ResolvedName {
+                        name: n.name,
+                        category: n.category,
+                    },
+                )
+            }));
+        }
+        Ok(resolved)
+    }
+
+    /// Resolve a URL path (with its parameters already filled in) back
+    /// to the `operationId` of the endpoint it was built from.
+    ///
+    /// Returns `None` if neither the spec nor the static paths have
+    /// a matching path.
+    pub(crate) fn get_op_id_for_endpoint(&self, method: &str, endpoint: &str) -> Option<String> {
+        let segments = endpoint.trim_matches('/').split('/').count();
+        self.path_index
+            .get(&segments)?
+            .iter()
+            .find(|(m, template, _)| {
+                m.eq_ignore_ascii_case(method) && path_matches(template, endpoint)
+            })
+            .map(|(_, _, op_id)| op_id.clone())
+    }
+
+    /// Get the `operationId`s in the spec that don't have a wrapper
+    /// function in one of the groups, sorted.
+    ///
+    /// Useful for reporting coverage; see [`WRAPPED_OP_IDS`] for the
+    /// list of endpoints that are wrapped.
+    ///
+    /// If the spec has not yet been retrieved when calling this function,
+    /// this function will return an error.
+    pub fn unwrapped_op_ids(&self) -> EsiResult<Vec<String>> {
+        let data = self.spec.as_ref().ok_or(EsiError::EmptySpec)?;
+        let paths = data["paths"]
+            .as_object()
+            .ok_or_else(|| EsiError::FailedSpecParse("Getting paths".to_owned()))?;
+        let mut op_ids: Vec<String> = paths
+            .values()
+            .filter_map(|path| path.as_object())
+            .flat_map(|path| path.values())
+            .filter_map(|method| method["operationId"].as_str())
+            .filter(|op_id| !WRAPPED_OP_IDS.contains(op_id))
+            .map(|op_id| op_id.to_owned())
+            .collect();
+        op_ids.sort();
+        op_ids.dedup();
+        Ok(op_ids)
+    }
+
+    /// Retrieve this struct's OpenAPI specification.
+    ///
+    /// Use in tandem with [EsiBuilder::spec].
+    pub fn get_spec(&self) -> Option<&Value> {
+        self.spec.as_deref()
+    }
+
+    /// Get a shared handle to this struct's OpenAPI specification.
+    ///
+    /// Cloning the handle doesn't copy the spec. Use in tandem with
+    /// [EsiBuilder::shared_spec].
+    pub fn shared_spec(&self) -> Option<Arc<Value>> {
+        self.spec.clone()
+    }
+
+    /// Call endpoints under the "alliance" group in ESI.
+    #[cfg(feature = "alliance")]
+    pub fn group_alliance(&self) -> AllianceGroup<'_> {
+        AllianceGroup { esi: self }
+    }
+
+    /// Call endpoints under the "Assets" group in ESI.
+    #[cfg(feature = "assets")]
+    pub fn group_assets(&self) -> AssetsGroup<'_> {
+        AssetsGroup { esi: self }
+    }
+
+    /// Call endpoints under the "Bookmarks" group in ESI.
+    #[cfg(feature = "bookmarks")]
+    pub fn group_bookmarks(&self) -> BookmarksGroup<'_> {
+        BookmarksGroup { esi: self }
+    }
 
     /// Call endpoints under the "Calendar" group in ESI.
-    pub fn group_calendar(&self) -> CalendarGroup {
+    #[cfg(feature = "calendar")]
+    pub fn group_calendar(&self) -> CalendarGroup<'_> {
         CalendarGroup { esi: self }
     }
 
     /// Call endpoints under the "Character" group in ESI.
-    pub fn group_character(&self) -> CharacterGroup {
+    #[cfg(feature = "character")]
+    pub fn group_character(&self) -> CharacterGroup<'_> {
         CharacterGroup { esi: self }
     }
 
     /// Call endpoints under the "Clones" group in ESI.
-    pub fn group_clones(&self) -> ClonesGroup {
+    #[cfg(feature = "clones")]
+    pub fn group_clones(&self) -> ClonesGroup<'_> {
         ClonesGroup { esi: self }
     }
 
     /// Call endpoints under the "Contacts" group in ESI.
-    pub fn group_contacts(&self) -> ContactsGroup {
+    #[cfg(feature = "contacts")]
+    pub fn group_contacts(&self) -> ContactsGroup<'_> {
         ContactsGroup { esi: self }
     }
 
     /// Call endpoints under the "Contracts" group in ESI.
-    pub fn group_contracts(&self) -> ContractsGroup {
+    #[cfg(feature = "contracts")]
+    pub fn group_contracts(&self) -> ContractsGroup<'_> {
         ContractsGroup { esi: self }
     }
 
     /// Call endpoints under the "Corporation" group in ESI.
-    pub fn group_corporation(&self) -> CorporationGroup {
+    #[cfg(feature = "corporation")]
+    pub fn group_corporation(&self) -> CorporationGroup<'_> {
         CorporationGroup { esi: self }
     }
 
     /// Call endpoints under the "Dogma" group in ESI.
-    pub fn group_dogma(&self) -> DogmaGroup {
+    #[cfg(feature = "dogma")]
+    pub fn group_dogma(&self) -> DogmaGroup<'_> {
         DogmaGroup { esi: self }
     }
 
     /// Call endpoints under the "FactionWarfare" group in ESI.
-    pub fn group_faction_warfare(&self) -> FactionWarfareGroup {
+    #[cfg(feature = "faction_warfare")]
+    pub fn group_faction_warfare(&self) -> FactionWarfareGroup<'_> {
         FactionWarfareGroup { esi: self }
     }
 
     /// Call endpoints under the "Fittings" group in ESI.
-    pub fn group_fittings(&self) -> FittingsGroup {
+    #[cfg(feature = "fittings")]
+    pub fn group_fittings(&self) -> FittingsGroup<'_> {
         FittingsGroup { esi: self }
     }
 
     /// Call endpoints under the "Fleets" group in ESI.
-    pub fn group_fleets(&self) -> FleetsGroup {
+    #[cfg(feature = "fleets")]
+    pub fn group_fleets(&self) -> FleetsGroup<'_> {
         FleetsGroup { esi: self }
     }
 
     /// Call endpoints under the "Incursions" group in ESI.
-    pub fn group_incursions(&self) -> IncursionsGroup {
+    #[cfg(feature = "incursions")]
+    pub fn group_incursions(&self) -> IncursionsGroup<'_> {
         IncursionsGroup { esi: self }
     }
 
     /// Call endpoints under the "Industry" group in ESI.
-    pub fn group_industry(&self) -> IndustryGroup {
+    #[cfg(feature = "industry")]
+    pub fn group_industry(&self) -> IndustryGroup<'_> {
         IndustryGroup { esi: self }
     }
 
     /// Call endpoints under the "Insurance" group in ESI.
-    pub fn group_insurance(&self) -> InsuranceGroup {
+    #[cfg(feature = "insurance")]
+    pub fn group_insurance(&self) -> InsuranceGroup<'_> {
         InsuranceGroup { esi: self }
     }
 
     /// Call endpoints under the "Killmails" group in ESI.
-    pub fn group_killmails(&self) -> KillmailsGroup {
+    #[cfg(feature = "killmails")]
+    pub fn group_killmails(&self) -> KillmailsGroup<'_> {
         KillmailsGroup { esi: self }
     }
 
     /// Call endpoints under the "Location" group in ESI.
-    pub fn group_location(&self) -> LocationGroup {
+    #[cfg(feature = "location")]
+    pub fn group_location(&self) -> LocationGroup<'_> {
         LocationGroup { esi: self }
     }
 
     /// Call endpoints under the "Loyalty" group in ESI.
-    pub fn group_loyalty(&self) -> LoyaltyGroup {
+    #[cfg(feature = "loyalty")]
+    pub fn group_loyalty(&self) -> LoyaltyGroup<'_> {
         LoyaltyGroup { esi: self }
     }
 
     /// Call endpoints under the "Mail" group in ESI.
-    pub fn group_mail(&self) -> MailGroup {
+    #[cfg(feature = "mail")]
+    pub fn group_mail(&self) -> MailGroup<'_> {
         MailGroup { esi: self }
     }
 
     /// Call endpoints under the "Market" group in ESI.
-    pub fn group_market(&self) -> MarketGroup {
+    #[cfg(feature = "market")]
+    pub fn group_market(&self) -> MarketGroup<'_> {
         MarketGroup { esi: self }
     }
 
     /// Call endpoints under the "Opportunities" group in ESI.
-    pub fn group_opportunities(&self) -> OpportunitiesGroup {
+    #[cfg(feature = "opportunities")]
+    pub fn group_opportunities(&self) -> OpportunitiesGroup<'_> {
         OpportunitiesGroup { esi: self }
     }
 
     /// Call endpoints under the "PlanetaryInteraction" group in ESI.
-    pub fn group_planetary_interaction(&self) -> PlanetaryInteractionGroup {
+    #[cfg(feature = "planetary_interaction")]
+    pub fn group_planetary_interaction(&self) -> PlanetaryInteractionGroup<'_> {
         PlanetaryInteractionGroup { esi: self }
     }
 
     /// Call endpoints under the "Routes" group in ESI.
-    pub fn group_routes(&self) -> RoutesGroup {
+    #[cfg(feature = "routes")]
+    pub fn group_routes(&self) -> RoutesGroup<'_> {
         RoutesGroup { esi: self }
     }
 
     /// Call endpoints under the "Search" group in ESI.
-    pub fn group_search(&self) -> SearchGroup {
+    #[cfg(feature = "search")]
+    pub fn group_search(&self) -> SearchGroup<'_> {
         SearchGroup { esi: self }
     }
 
     /// Call endpoints under the "Skills" group in ESI.
-    pub fn group_skills(&self) -> SkillsGroup {
+    #[cfg(feature = "skills")]
+    pub fn group_skills(&self) -> SkillsGroup<'_> {
         SkillsGroup { esi: self }
     }
 
     /// Call endpoints under the "Sovereignty" group in ESI.
-    pub fn group_sovereignty(&self) -> SovereigntyGroup {
+    #[cfg(feature = "sovereignty")]
+    pub fn group_sovereignty(&self) -> SovereigntyGroup<'_> {
         SovereigntyGroup { esi: self }
     }
 
     /// Call endpoints under the "Status" group in ESI.
-    pub fn group_status(&self) -> StatusGroup {
+    #[cfg(feature = "status")]
+    pub fn group_status(&self) -> StatusGroup<'_> {
         StatusGroup { esi: self }
     }
 
     /// Call endpoints under the "Universe" group in ESI.
-    pub fn group_universe(&self) -> UniverseGroup {
+    #[cfg(feature = "universe")]
+    pub fn group_universe(&self) -> UniverseGroup<'_> {
         UniverseGroup { esi: self }
     }
 
     /// Call endpoints under the "UserInterface" group in ESI.
-    pub fn group_user_interface(&self) -> UserInterfaceGroup {
+    #[cfg(feature = "user_interface")]
+    pub fn group_user_interface(&self) -> UserInterfaceGroup<'_> {
         UserInterfaceGroup { esi: self }
     }
 
     /// Call endpoints under the "Wallet" group in ESI.
-    pub fn group_wallet(&self) -> WalletGroup {
+    #[cfg(feature = "wallet")]
+    pub fn group_wallet(&self) -> WalletGroup<'_> {
         WalletGroup { esi: self }
     }
 
     /// Call endpoints under the "Wars" group in ESI.
-    pub fn group_wars(&self) -> WarsGroup {
+    #[cfg(feature = "wars")]
+    pub fn group_wars(&self) -> WarsGroup<'_> {
         WarsGroup { esi: self }
     }
 }
 
+/// Lookup of a URL path's segment count to the HTTP method, path
+/// template and `operationId` of each endpoint with that many segments.
+pub(crate) type PathIndex = HashMap<usize, Vec<(String, String, String)>>;
+
+/// Build the reverse lookup of URL path templates to `operationId`s.
+///
+/// The HTTP method is taken from the `operationId`'s prefix, which ESI
+/// uses for every operation. Within each segment count, templates with
+/// fewer parameters come first, so literal path segments are preferred.
+fn build_path_index(op_id_map: Option<&HashMap<String, String>>) -> PathIndex {
+    let paths = op_id_map
+        .into_iter()
+        .flatten()
+        .map(|(op_id, path)| (op_id.as_str(), path.as_str()));
+    #[cfg(feature = "static_paths")]
+    let paths = paths.chain(crate::vendored::VENDORED_PATHS.iter().copied());
+
+    let mut index = PathIndex::new();
+    for (op_id, path) in paths {
+        let Some((method, _)) = op_id.split_once('_') else {
+            continue;
+        };
+        let template = path.trim_matches('/');
+        index.entry(template.split('/').count()).or_default().push((
+            method.to_owned(),
+            template.to_owned(),
+            op_id.to_owned(),
+        ));
+    }
+    for templates in index.values_mut() {
+        templates.sort_by_cached_key(|(_, template, op_id)| {
+            let params = template.split('/').filter(|t| is_path_param(t)).count();
+            (params, template.clone(), op_id.clone())
+        });
+    }
+    index
+}
+
+/// Get the paths in the Swagger spec, sorted, so that lookups that
+/// could match more than one path always pick the same one.
+fn sorted_paths(spec: &Value) -> EsiResult<Vec<(&String, &Value)>> {
+    let paths = spec["paths"]
+        .as_object()
+        .ok_or_else(|| EsiError::FailedSpecParse("Getting paths".to_owned()))?;
+    let mut paths: Vec<_> = paths.iter().collect();
+    paths.sort_unstable_by_key(|(path_str, _)| *path_str);
+    Ok(paths)
+}
+
+/// Build the lookup of `operationId` to URL path from the Swagger spec.
+///
+/// If an `operationId` appears under more than one path, the first
+/// path in sorted order is used and a warning is logged.
+fn build_op_id_map(spec: &Value) -> EsiResult<HashMap<String, String>> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    for (path_str, path_obj) in sorted_paths(spec)? {
+        let path = path_obj
+            .as_object()
+            .ok_or_else(|| EsiError::FailedSpecParse("Parsing a path".to_owned()))?;
+        // the paths contain a leading slash, so strip it
+        let stripped: String = path_str.chars().skip(1).collect();
+        for method in path.values() {
+            let Some(operation_id) = method["operationId"].as_str() else {
+                continue;
+            };
+            match map.get(operation_id) {
+                Some(existing) if *existing != stripped => warn!(
+                    "operationId {} is used by both {} and {}; using {}",
+                    operation_id, existing, stripped, existing
+                ),
+                Some(_) => {}
+                None => {
+                    map.insert(operation_id.to_owned(), stripped.clone());
+                }
+            }
+        }
+    }
+    Ok(map)
+}
+
+/// Find the path, HTTP method, and definition of an `operationId` in the spec.
+fn find_operation_object<'a>(
+    spec: &'a Value,
+    op_id: &str,
+) -> EsiResult<(&'a str, &'a str, &'a Value)> {
+    sorted_paths(spec)?
+        .into_iter()
+        .filter_map(|(path_str, path_obj)| Some((path_str, path_obj.as_object()?)))
+        .flat_map(|(path_str, path)| {
+            path.iter()
+                .map(move |(method, op)| (path_str.as_str(), method.as_str(), op))
+        })
+        .find(|(_, _, op)| op["operationId"].as_str() == Some(op_id))
+        .ok_or_else(|| EsiError::UnknownOperationID(op_id.to_owned()))
+}
+
+/// Find the path (without its leading slash), HTTP method, and request
+/// type of an `operationId` in the spec.
+///
+/// Operations with any security requirements are authenticated.
+fn find_operation(spec: &Value, op_id: &str) -> EsiResult<(String, String, RequestType)> {
+    let (path_str, method, op) = find_operation_object(spec, op_id)?;
+    let request_type = match op["security"].as_array() {
+        Some(security) if !security.is_empty() => RequestType::Authenticated,
+        _ => RequestType::Public,
+    };
+    Ok((
+        path_str.chars().skip(1).collect(),
+        method.to_uppercase(),
+        request_type,
+    ))
+}
+
+/// Get the SSO scopes listed in an operation's `evesso` security requirement.
+fn operation_scopes(op: &Value) -> Vec<String> {
+    op["security"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|requirement| requirement["evesso"].as_array())
+        .flatten()
+        .filter_map(|scope| scope.as_str().map(|s| s.to_owned()))
+        .collect()
+}
+
+/// Fill in the parameters of a path template, like `{character_id}`.
+fn fill_path_params(template: &str, params: &[(&str, &str)]) -> EsiResult<String> {
+    template
+        .split('/')
+        .map(|segment| {
+            if !is_path_param(segment) {
+                return Ok(segment);
+            }
+            let name = &segment[1..segment.len() - 1];
+            params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| EsiError::MissingPathParameter(name.to_owned()))
+        })
+        .collect::<EsiResult<Vec<_>>>()
+        .map(|segments| segments.join("/"))
+}
+
+/// Check whether a path segment is a parameter, like `{character_id}`.
+fn is_path_param(segment: &str) -> bool {
+    segment.starts_with('{') && segment.ends_with('}')
+}
+
+/// Check whether a URL path (with its parameters filled in) was
+/// built from a path template.
+fn path_matches(template: &str, endpoint: &str) -> bool {
+    let template = template.trim_matches('/');
+    let endpoint = endpoint.trim_matches('/');
+    template.split('/').count() == endpoint.split('/').count()
+        && template
+            .split('/')
+            .zip(endpoint.split('/'))
+            .all(|(t, e)| t == e || is_path_param(t))
+}
+
+/// Build the key for a response in the cache from its path and (sorted) query.
+fn cache_key(endpoint: &str, query: &[(&str, &str)]) -> String {
+    format!("{endpoint}?{query:?}")
+}
+
+/// Build the `Authorization` header value for an access token.
+fn bearer_header(access_token: &str) -> EsiResult<HeaderValue> {
+    let mut value = HeaderValue::from_str(&format!("Bearer {access_token}"))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// Get how long until the time in the `Expires` header, if it's in the future.
+#[cfg(feature = "market")]
+fn expires_in(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let expires = headers.get(header::EXPIRES)?.to_str().ok()?;
+    let expires = DateTime::parse_from_rfc2822(expires).ok()?;
+    (expires.with_timezone(&Utc) - now)
+        .to_std()
+        .ok()
+        .filter(|ttl| !ttl.is_zero())
+}
+
+/// Read the total page count from the `X-Pages` header, defaulting to 1
+/// when it's absent and failing when it isn't a positive number that
+/// fits in a page number.
+fn parse_total_pages(headers: &HeaderMap) -> EsiResult<u32> {
+    let Some(value) = headers.get("x-pages") else {
+        return Ok(1);
+    };
+    value
+        .to_str()
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|pages| (1..=i32::MAX as u32).contains(pages))
+        .ok_or_else(|| EsiError::InvalidPageCount(String::from_utf8_lossy(value.as_bytes()).into()))
+}
+
+/// Get the host (and port, if not the default) of a URL.
+pub(crate) fn login_host(url: &reqwest::Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_owned(),
+    }
+}
+
 /// Get the current system timestamp since the epoch.
 fn current_time_millis() -> Result<i64, EsiError> {
     Ok(SystemTime::now()
@@ -802,33 +2024,846 @@ fn current_time_millis() -> Result<i64, EsiError> {
 
 #[cfg(test)]
 mod tests {
-    use super::AuthenticateResponse;
+    use super::{build_op_id_map, build_path_index, fill_path_params, find_operation};
+    use crate::prelude::*;
+    use std::sync::Arc;
+
+    #[cfg(feature = "market")]
+    #[test]
+    fn test_expires_in() {
+        use chrono::{TimeZone, Utc};
+        use reqwest::header::{HeaderMap, HeaderValue, EXPIRES};
+        use std::time::Duration;
+
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 11, 0, 0).unwrap();
+        let mut headers = HeaderMap::new();
+        assert_eq!(super::expires_in(&headers, now), None);
+
+        headers.insert(
+            EXPIRES,
+            HeaderValue::from_static("Mon, 01 Jan 2024 11:05:00 GMT"),
+        );
+        assert_eq!(
+            super::expires_in(&headers, now),
+            Some(Duration::from_secs(300))
+        );
+
+        headers.insert(
+            EXPIRES,
+            HeaderValue::from_static("Mon, 01 Jan 2024 10:55:00 GMT"),
+        );
+        assert_eq!(super::expires_in(&headers, now), None);
+    }
+
+    #[test]
+    fn test_paged_has_next() {
+        let page = super::Paged::<i32> {
+            items: vec![1, 2],
+            page: 3,
+            total_pages: 12,
+        };
+        assert!(page.has_next());
+        let last = super::Paged { page: 12, ..page };
+        assert!(!last.has_next());
+    }
+
+    #[test]
+    fn test_fork_for_user() {
+        let spec = serde_json::json!({ "paths": {} });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .access_token(Some("abc"))
+            .spec(Some(spec))
+            .build()
+            .unwrap();
+        let auth = EsiAuthState {
+            access_token: Some("def".to_owned()),
+            access_expiration: Some(1),
+            refresh_token: None,
+            scopes: Vec::new(),
+        };
+        let fork = esi.fork_for_user(auth.clone());
+
+        assert_eq!(fork.auth_state(), auth);
+        assert_eq!(esi.access_token().as_deref(), Some("abc"));
+        assert!(std::sync::Arc::ptr_eq(
+            esi.spec.as_ref().unwrap(),
+            fork.spec.as_ref().unwrap()
+        ));
+        assert!(std::sync::Arc::ptr_eq(
+            &esi.response_cache,
+            &fork.response_cache
+        ));
+    }
 
     #[test]
-    fn test_authenticateresponse_deserialize() {
-        let source = r#"{
-            "access_token": "abc",
-            "expires_in": 1000,
-            "refresh_token": "def"
-          }"#;
-        let data: AuthenticateResponse = serde_json::from_str(source).unwrap();
+    fn test_clones_share_tokens() {
+        let esi = EsiBuilder::new().user_agent("d").build().unwrap();
+        let clone = esi.clone();
+        let fork = esi.fork_for_user(EsiAuthState::default());
+        esi.set_auth_state(EsiAuthState {
+            access_token: Some("abc".to_owned()),
+            ..EsiAuthState::default()
+        });
 
-        assert_eq!(data.access_token, "abc");
-        assert_eq!(data.expires_in, 1000);
-        assert_eq!(data.refresh_token, Some("def".to_owned()));
+        assert_eq!(clone.access_token().as_deref(), Some("abc"));
+        assert_eq!(fork.access_token(), None);
+        assert_eq!(
+            clone.tokens.read().unwrap().bearer_header.as_ref().unwrap(),
+            "Bearer abc"
+        );
     }
 
     #[test]
-    fn test_authenticateresponse_deserialize_no_refresh_token() {
-        let source = r#"{
-            "access_token": "abc",
-            "expires_in": 1000,
-            "refresh_token": null
-          }"#;
-        let data: AuthenticateResponse = serde_json::from_str(source).unwrap();
-
-        assert_eq!(data.access_token, "abc");
-        assert_eq!(data.expires_in, 1000);
-        assert_eq!(data.refresh_token, None);
+    fn test_auth_headers_singularity() {
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .client_id("a")
+            .client_secret("b")
+            .callback_url("c")
+            .environment(EsiEnvironment::Singularity)
+            .build()
+            .unwrap();
+        let headers = esi.get_auth_headers().unwrap();
+        assert_eq!(headers["host"], "sisilogin.testeveonline.com");
+    }
+
+    #[cfg(feature = "validate_jwt")]
+    #[tokio::test]
+    async fn test_authenticate_uses_token_url_server() {
+        use crate::test_server::{serve, Response};
+        use jsonwebtoken::{Algorithm, EncodingKey, Header};
+        use std::sync::{Arc, Mutex};
+
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("resources/test/keys");
+        let private_key = std::fs::read_to_string(root.join("jwtRS256.key")).unwrap();
+        let jwks = std::fs::read_to_string(root.join("jwks.json")).unwrap();
+
+        // the token has to name the server as its issuer, which isn't known
+        // until it's started, so it's filled in afterward
+        let token = Arc::new(Mutex::new(String::new()));
+        let hosts = Arc::new(Mutex::new(Vec::new()));
+        let base_url = {
+            let token = token.clone();
+            let hosts = hosts.clone();
+            serve(move |request| {
+                hosts
+                    .lock()
+                    .unwrap()
+                    .push(request.header("host").unwrap_or_default().to_owned());
+                let host = request.header("host").unwrap_or_default();
+                match request.target.as_str() {
+                    "/v2/oauth/token" => {
+                        assert_eq!(request.method, "POST");
+                        assert!(request.body.contains("code=code"));
+                        Response::json(
+                            serde_json::json!({
+                                "access_token": *token.lock().unwrap(),
+                                "expires_in": 1199,
+                                "token_type": "Bearer",
+                                "refresh_token": "def",
+                            })
+                            .to_string(),
+                        )
+                    }
+                    "/.well-known/oauth-authorization-server" => Response::json(
+                        serde_json::json!({ "jwks_uri": format!("http://{host}/oauth/jwks") })
+                            .to_string(),
+                    ),
+                    "/oauth/jwks" => Response::json(jwks.clone()),
+                    _ => Response::json("").status(404),
+                }
+            })
+            .await
+        };
+        let host = base_url
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_owned();
+        let now = chrono::Utc::now().timestamp();
+        *token.lock().unwrap() = jsonwebtoken::encode(
+            &Header::new(Algorithm::RS256),
+            &serde_json::json!({
+                "aud": ["a", "EVE Online"],
+                "azp": "a",
+                "exp": now + 1200,
+                "iat": now,
+                "iss": format!("https://{host}"),
+                "jti": "e7a53e01",
+                "kid": "JWT-Signature-Key",
+                "name": "Some Character",
+                "owner": "abc123=",
+                "region": "world",
+                "scp": "esi-skills.read_skills.v1",
+                "sub": "CHARACTER:EVE:2112000000",
+                "tenant": "singularity",
+                "tier": "test",
+            }),
+            &EncodingKey::from_rsa_pem(private_key.as_bytes()).unwrap(),
+        )
+        .unwrap();
+
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .client_id("a")
+            .client_secret("b")
+            .callback_url("c")
+            .environment(EsiEnvironment::Singularity)
+            .token_url(&format!("{base_url}v2/oauth/token"))
+            .build()
+            .unwrap();
+        let claims = esi.authenticate("code", None).await.unwrap().unwrap();
+
+        assert_eq!(claims.tenant, "singularity");
+        assert_eq!(esi.refresh_token().as_deref(), Some("def"));
+        assert_eq!(esi.granted_scopes(), vec!["esi-skills.read_skills.v1"]);
+        assert!(hosts.lock().unwrap().iter().all(|h| *h == host));
+        assert_eq!(hosts.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_tokens_without_scopes() {
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|request| {
+            assert_eq!(request.method, "POST");
+            assert!(request.body.contains("refresh_token=old"));
+            Response::json(
+                serde_json::json!({
+                    "access_token": "not-a-jwt",
+                    "expires_in": 1199,
+                    "token_type": "Bearer",
+                    "refresh_token": "new",
+                })
+                .to_string(),
+            )
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .client_id("a")
+            .client_secret("b")
+            .callback_url("c")
+            .token_url(&format!("{base_url}v2/oauth/token"))
+            .build()
+            .unwrap();
+        esi.refresh_access_token(Some("old")).await.unwrap();
+
+        assert_eq!(esi.access_token().as_deref(), Some("not-a-jwt"));
+        assert_eq!(esi.refresh_token().as_deref(), Some("new"));
+        assert!(esi.granted_scopes().is_empty());
+    }
+
+    #[test]
+    fn test_should_log_status() {
+        use reqwest::StatusCode;
+
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .suppress_log_for_statuses(&[404])
+            .build()
+            .unwrap();
+        assert!(!esi.should_log_status(StatusCode::NOT_FOUND));
+        assert!(esi.should_log_status(StatusCode::BAD_GATEWAY));
+
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .log_http_errors(false)
+            .build()
+            .unwrap();
+        assert!(!esi.should_log_status(StatusCode::BAD_GATEWAY));
+    }
+
+    #[test]
+    fn test_is_authenticated() {
+        const NOW: i64 = 1_700_000_000_000;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .clock(|| NOW)
+            .build()
+            .unwrap();
+        assert!(!esi.is_authenticated());
+        assert!(!esi.is_refreshable());
+
+        esi.set_auth_state(EsiAuthState {
+            access_token: Some("abc".to_owned()),
+            access_expiration: Some(NOW + 60_000),
+            refresh_token: Some("def".to_owned()),
+            scopes: Vec::new(),
+        });
+        assert!(esi.is_authenticated());
+        assert!(esi.is_refreshable());
+
+        esi.set_auth_state(EsiAuthState {
+            access_expiration: Some(NOW - 1),
+            ..esi.auth_state()
+        });
+        assert!(!esi.is_authenticated());
+    }
+
+    #[tokio::test]
+    async fn test_expired_token_with_clock() {
+        const NOW: i64 = 1_700_000_000_000;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .access_token(Some("abc"))
+            .access_expiration(Some(NOW - 1))
+            .clock(|| NOW)
+            .build()
+            .unwrap();
+        let result: EsiResult<serde_json::Value> = esi
+            .query(
+                "GET",
+                RequestType::Authenticated,
+                "latest/status/",
+                None,
+                None,
+            )
+            .await;
+        assert!(matches!(result, Err(EsiError::AccessTokenExpired)));
+    }
+
+    #[test]
+    fn test_get_op_id_for_endpoint() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v5/characters/{character_id}/": { "get": { "operationId": "get_characters_character_id" } },
+                "/v2/characters/{character_id}/assets/": { "get": { "operationId": "get_characters_character_id_assets" } },
+                "/v1/characters/affiliation/": { "post": { "operationId": "post_characters_affiliation" } },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .spec(Some(spec))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            esi.get_op_id_for_endpoint("GET", "v5/characters/123/"),
+            Some("get_characters_character_id".to_owned())
+        );
+        assert_eq!(
+            esi.get_op_id_for_endpoint("POST", "v1/characters/affiliation/"),
+            Some("post_characters_affiliation".to_owned())
+        );
+        assert_eq!(
+            esi.get_op_id_for_endpoint("GET", "v1/characters/affiliation/"),
+            None
+        );
+        assert_eq!(esi.get_op_id_for_endpoint("GET", "v1/unknown/"), None);
+    }
+
+    #[test]
+    fn test_get_op_id_for_endpoint_without_spec() {
+        let mut esi = EsiBuilder::new().user_agent("d").build().unwrap();
+        // as when falling back to the vendored paths
+        esi.op_id_map = Some(Arc::new(crate::vendored::vendored_op_id_map()));
+        esi.path_index = Arc::new(build_path_index(esi.op_id_map.as_deref()));
+
+        assert_eq!(
+            esi.get_op_id_for_endpoint("GET", "latest/characters/123/"),
+            Some("get_characters_character_id".to_owned())
+        );
+        assert_eq!(
+            esi.get_op_id_for_endpoint("POST", "latest/characters/affiliation/"),
+            Some("post_characters_affiliation".to_owned())
+        );
+        assert_eq!(
+            esi.get_op_id_for_endpoint("DELETE", "latest/characters/123/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_resolve_many() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/status/": { "get": { "operationId": "get_status" } },
+                "/v2/alliances/": { "get": { "operationId": "get_alliances" } },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .spec(Some(spec))
+            .build()
+            .unwrap();
+
+        let paths = esi.resolve_many(&["get_status", "get_alliances"]).unwrap();
+        assert_eq!(paths["get_status"], "v1/status/");
+        assert_eq!(paths["get_alliances"], "v2/alliances/");
+        assert!(esi.resolve_many(&["get_status", "get_nothing"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cache_key_ignores_param_order() {
+        use crate::test_server::{serve, Response};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let base_url = {
+            let requests = requests.clone();
+            serve(move |_| {
+                requests.fetch_add(1, Ordering::SeqCst);
+                Response::json("42")
+            })
+            .await
+        };
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/cached/": { "get": { "operationId": "get_cached" } },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(spec))
+            .cache_op("get_cached", std::time::Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        for query in [[("a", "1"), ("b", "2")], [("b", "2"), ("a", "1")]] {
+            let value: i32 = esi
+                .query("GET", RequestType::Public, "v1/cached/", Some(&query), None)
+                .await
+                .unwrap();
+            assert_eq!(value, 42);
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_expires_with_clock() {
+        use crate::test_server::{serve, Response};
+        use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        static NOW: AtomicI64 = AtomicI64::new(1_700_000_000_000);
+        let requests = Arc::new(AtomicUsize::new(0));
+        let base_url = {
+            let requests = requests.clone();
+            serve(move |_| {
+                let count = requests.fetch_add(1, Ordering::SeqCst) + 1;
+                Response::json(count.to_string())
+            })
+            .await
+        };
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/cached/": { "get": { "operationId": "get_cached" } },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(spec))
+            .cache_op("get_cached", std::time::Duration::from_secs(60))
+            .clock(|| NOW.load(Ordering::SeqCst))
+            .build()
+            .unwrap();
+        let get = || esi.query::<usize>("GET", RequestType::Public, "v1/cached/", None, None);
+
+        assert_eq!(get().await.unwrap(), 1);
+        NOW.fetch_add(59_000, Ordering::SeqCst);
+        assert_eq!(get().await.unwrap(), 1);
+        NOW.fetch_add(1_000, Ordering::SeqCst);
+        assert_eq!(get().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_query_page() {
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|request| {
+            let (path, query) = request.target.split_once('?').unwrap();
+            assert_eq!(path, "/v1/paged/");
+            assert!(query.contains("type=sell"));
+            let page = query
+                .split('&')
+                .find_map(|param| param.strip_prefix("page="));
+            let body = match page {
+                Some("1") => "[1,2]",
+                Some("2") => "[3,4]",
+                Some("3") => "[5]",
+                _ => return Response::json("").status(404),
+            };
+            Response::json(body).header("x-pages", "3")
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+
+        let mut items = Vec::new();
+        let mut page = 1;
+        loop {
+            let paged: super::Paged<i64> = esi
+                .query_page(
+                    "GET",
+                    RequestType::Public,
+                    "v1/paged/",
+                    Some(&[("type", "sell")]),
+                    Some(page),
+                )
+                .await
+                .unwrap();
+            assert_eq!(paged.page, page);
+            assert_eq!(paged.total_pages, 3);
+            let has_next = paged.has_next();
+            items.extend(paged.items);
+            if !has_next {
+                break;
+            }
+            page += 1;
+        }
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_query_all_pages() {
+        use crate::test_server::{serve, Response};
+        use std::sync::{Arc, Mutex};
+
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let base_url = {
+            let requested = requested.clone();
+            serve(move |request| {
+                let (path, query) = request.target.split_once('?').unwrap();
+                assert_eq!(path, "/v1/paged/");
+                assert!(query.contains("type=sell"));
+                let page = query
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("page="))
+                    .unwrap()
+                    .to_owned();
+                let body = match page.as_str() {
+                    "1" => "[1,2]",
+                    "2" => "[3,4]",
+                    "3" => "[5]",
+                    _ => return Response::json("").status(404),
+                };
+                requested.lock().unwrap().push(page);
+                Response::json(body).header("x-pages", "3")
+            })
+            .await
+        };
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+
+        let items: Vec<i64> = esi
+            .query_all_pages(
+                "GET",
+                RequestType::Public,
+                "v1/paged/",
+                Some(&[("type", "sell")]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+        let mut requested = requested.lock().unwrap().clone();
+        requested.sort();
+        assert_eq!(requested, vec!["1", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn test_query_all_pages_failed_page() {
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|request| {
+            if request.target.contains("page=2") {
+                Response::json("{}").status(500)
+            } else {
+                Response::json("[1]").header("x-pages", "3")
+            }
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+
+        let result: EsiResult<Vec<i64>> = esi
+            .query_all_pages("GET", RequestType::Public, "v1/paged/", None)
+            .await;
+        assert_eq!(result.unwrap_err().status(), Some(500));
+    }
+
+    #[tokio::test]
+    async fn test_get_total_pages() {
+        use crate::test_server::{serve, Response};
+
+        // the path says which X-Pages value to send back
+        let base_url = serve(|request| {
+            let (path, query) = request.target.split_once('?').unwrap();
+            assert!(query.contains("page=1"));
+            let response = Response::json("[]");
+            match path.trim_matches('/').rsplit_once('/').unwrap().1 {
+                "none" => response,
+                pages => response.header("x-pages", pages),
+            }
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+        let get = |endpoint: &'static str| esi.get_total_pages(RequestType::Public, endpoint, None);
+
+        assert_eq!(get("v1/paged/12/").await.unwrap(), 12);
+        assert_eq!(get("v1/paged/none/").await.unwrap(), 1);
+        for invalid in [
+            "v1/paged/0/",
+            "v1/paged/-3/",
+            "v1/paged/abc/",
+            "v1/paged/3000000000/",
+        ] {
+            assert!(matches!(
+                get(invalid).await,
+                Err(EsiError::InvalidPageCount(_))
+            ));
+        }
+        // paging through the results reads X-Pages the same way
+        let items: EsiResult<Vec<i64>> = esi
+            .query_all_pages("GET", RequestType::Public, "v1/paged/0/", None)
+            .await;
+        assert!(matches!(items, Err(EsiError::InvalidPageCount(_))));
+    }
+
+    #[tokio::test]
+    async fn test_query_html_error_page() {
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|request| {
+            assert_eq!(request.method, "GET");
+            assert!(request.target.starts_with("/latest/status/"));
+            assert_eq!(request.header("user-agent"), Some("d"));
+            assert!(request.body.is_empty());
+            let mut response =
+                Response::json("<html><body><h1>502 Bad Gateway</h1></body></html>").status(502);
+            response.headers = vec![
+                ("content-type".to_owned(), "text/html".to_owned()),
+                ("x-esi-request-id".to_owned(), "abc-123".to_owned()),
+            ];
+            response
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+
+        let result: EsiResult<serde_json::Value> = esi
+            .query("GET", RequestType::Public, "latest/status/", None, None)
+            .await;
+        match result {
+            Err(EsiError::NonJsonResponse {
+                status,
+                content_type,
+                request_id,
+            }) => {
+                assert_eq!(status, 502);
+                assert_eq!(content_type, "text/html");
+                assert_eq!(request_id.as_deref(), Some("abc-123"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_forbidden_keeps_request_id() {
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|_| {
+            let mut response =
+                Response::json(r#"{"error":"Character not in corporation"}"#).status(403);
+            response
+                .headers
+                .push(("x-esi-request-id".to_owned(), "abc-123".to_owned()));
+            response
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .build()
+            .unwrap();
+
+        let err = esi
+            .query::<serde_json::Value>("GET", RequestType::Public, "latest/status/", None, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EsiError::Forbidden { .. }));
+        assert_eq!(err.status(), Some(403));
+        assert_eq!(err.request_id(), Some("abc-123"));
+        assert!(err.to_string().ends_with("(request ID abc-123)"));
+    }
+
+    #[test]
+    fn test_unwrapped_op_ids() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/status/": { "get": { "operationId": "get_status" } },
+                "/v1/universe/ancestries/": { "get": { "operationId": "get_universe_ancestries" } },
+                "/v2/alliances/": { "get": { "operationId": "get_alliances" } },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .spec(Some(spec))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            esi.unwrapped_op_ids().unwrap(),
+            vec!["get_universe_ancestries"]
+        );
+    }
+
+    #[test]
+    fn test_find_operation() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/status/": { "get": { "operationId": "get_status" } },
+                "/v2/characters/{character_id}/fittings/": {
+                    "post": {
+                        "operationId": "post_characters_character_id_fittings",
+                        "security": [{ "evesso": ["esi-fittings.write_fittings.v1"] }]
+                    }
+                },
+            }
+        });
+
+        let (path, method, request_type) = find_operation(&spec, "get_status").unwrap();
+        assert_eq!(path, "v1/status/");
+        assert_eq!(method, "GET");
+        assert_eq!(request_type, RequestType::Public);
+
+        let (path, method, request_type) =
+            find_operation(&spec, "post_characters_character_id_fittings").unwrap();
+        assert_eq!(path, "v2/characters/{character_id}/fittings/");
+        assert_eq!(method, "POST");
+        assert_eq!(request_type, RequestType::Authenticated);
+
+        assert!(matches!(
+            find_operation(&spec, "get_nothing"),
+            Err(EsiError::UnknownOperationID(_))
+        ));
+    }
+
+    #[test]
+    fn test_required_scopes_for_op() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v1/status/": { "get": { "operationId": "get_status" } },
+                "/v2/characters/{character_id}/fittings/": {
+                    "post": {
+                        "operationId": "post_characters_character_id_fittings",
+                        "security": [{ "evesso": ["esi-fittings.write_fittings.v1"] }]
+                    }
+                },
+            }
+        });
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .spec(Some(spec))
+            .build()
+            .unwrap();
+
+        assert!(esi.required_scopes_for_op("get_status").unwrap().is_empty());
+        assert_eq!(
+            esi.required_scopes_for_op("post_characters_character_id_fittings")
+                .unwrap(),
+            vec!["esi-fittings.write_fittings.v1"]
+        );
+        assert!(matches!(
+            esi.required_scopes_for_op("get_nothing"),
+            Err(EsiError::UnknownOperationID(_))
+        ));
+    }
+
+    #[test]
+    fn test_fill_path_params() {
+        let template = "v2/characters/{character_id}/fittings/{fitting_id}/";
+        assert_eq!(
+            fill_path_params(template, &[("fitting_id", "5"), ("character_id", "1")]).unwrap(),
+            "v2/characters/1/fittings/5/"
+        );
+        assert!(matches!(
+            fill_path_params(template, &[("character_id", "1")]),
+            Err(EsiError::MissingPathParameter(name)) if name == "fitting_id"
+        ));
+    }
+
+    #[test]
+    fn test_request_body_form() {
+        let client = reqwest::Client::new();
+        let req = RequestBody::Form(&[("grant_type", "refresh_token"), ("token", "a b")])
+            .apply(client.post("https://esi.evetech.net/latest/"))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            req.headers()[reqwest::header::CONTENT_TYPE],
+            "application/x-www-form-urlencoded"
+        );
+        assert_eq!(
+            req.body().unwrap().as_bytes().unwrap(),
+            b"grant_type=refresh_token&token=a+b"
+        );
+    }
+
+    #[test]
+    fn test_request_body_json() {
+        let client = reqwest::Client::new();
+        let req = RequestBody::Json(r#"[1,2]"#)
+            .apply(client.post("https://esi.evetech.net/latest/"))
+            .build()
+            .unwrap();
+
+        assert_eq!(req.body().unwrap().as_bytes().unwrap(), b"[1,2]");
+    }
+
+    #[test]
+    fn test_build_op_id_map_duplicate() {
+        let spec = serde_json::json!({
+            "paths": {
+                "/v2/status/": { "get": { "operationId": "get_status" } },
+                "/v1/status/": { "get": { "operationId": "get_status" } },
+                "/v1/alliances/": { "get": { "operationId": "get_alliances" } },
+            }
+        });
+        let map = build_op_id_map(&spec).unwrap();
+
+        assert_eq!(map["get_status"], "v1/status/");
+        assert_eq!(map["get_alliances"], "v1/alliances/");
+        assert_eq!(find_operation(&spec, "get_status").unwrap().0, "v1/status/");
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .client_id("some-client-id")
+            .client_secret("some-client-secret")
+            .callback_url("http://localhost/callback")
+            .access_token(Some("some-access-token"))
+            .refresh_token(Some("some-refresh-token"))
+            .build()
+            .unwrap();
+        let debug = format!("{esi:?}");
+
+        assert!(debug.contains("some-client-id"));
+        assert!(debug.contains(r#"client_secret: Some("***")"#));
+        assert!(!debug.contains("some-client-secret"));
+        assert!(!debug.contains("some-access-token"));
+        assert!(!debug.contains("some-refresh-token"));
     }
 }
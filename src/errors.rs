@@ -14,6 +14,10 @@ pub enum EsiError {
     /// You need to specify either a client secret or enable application auth
     #[error("Authentication flow information missing. You need to either specify client_secret or enable application auth.")]
     MissingAuthenticationFlowInformation,
+    /// Error for required environment variables not being set
+    /// when calling `EsiBuilder::from_env`.
+    #[error("Missing required environment variable(s): {}", .0.join(", "))]
+    MissingEnvVars(Vec<String>),
     /// You have to retrieve the ESI spec via `Esi::update_spec`
     /// before making this call.
     #[error("Missing spec")]
@@ -30,8 +34,87 @@ pub enum EsiError {
     /// calls our to external resources for response codes that
     /// aren't valid as defined [by reqwest].
     /// [by reqwest]: https://docs.rs/reqwest/0.10.6/reqwest/struct.StatusCode.html#method.is_success
-    #[error("Invalid HTTP status code received: {0}")]
-    InvalidStatusCode(u16),
+    ///
+    /// When the response came from ESI, `request_id` holds the value of the
+    /// `X-ESI-Request-Id` response header; include it when reporting problems to CCP.
+    #[error(
+        "Invalid HTTP status code received: {status}{}",
+        request_id_suffix(request_id)
+    )]
+    InvalidStatusCode {
+        /// HTTP status code of the response.
+        status: u16,
+        /// Value of the response's `X-ESI-Request-Id` header, if present.
+        request_id: Option<String>,
+    },
+    /// Error for a response that isn't JSON, such as an HTML error page
+    /// from the gateway in front of ESI when it's under heavy load.
+    ///
+    /// Unlike `FailedJsonParse`, this doesn't mean that the response
+    /// didn't match the expected struct, and retrying later may succeed.
+    #[error(
+        "Received a non-JSON response ({content_type}) with status {status}{}",
+        request_id_suffix(request_id)
+    )]
+    NonJsonResponse {
+        /// HTTP status code of the response.
+        status: u16,
+        /// Value of the response's `Content-Type` header.
+        content_type: String,
+        /// Value of the response's `X-ESI-Request-Id` header, if present.
+        request_id: Option<String>,
+    },
+    /// Error for ESI rejecting the access token (HTTP 401), such as
+    /// when it has expired or been revoked.
+    #[error(
+        "Unauthorized: the access token was rejected{}",
+        request_id_suffix(request_id)
+    )]
+    Unauthorized {
+        /// Value of the response's `X-ESI-Request-Id` header, if present.
+        request_id: Option<String>,
+    },
+    /// Error for ESI refusing the request despite a valid access token
+    /// (HTTP 403), such as when the token is missing a required scope,
+    /// the character lacks a required corporation role, or the character
+    /// is not on a structure's access list.
+    #[error(
+        "Forbidden: the access token does not grant access to this resource{}",
+        request_id_suffix(request_id)
+    )]
+    Forbidden {
+        /// Value of the response's `X-ESI-Request-Id` header, if present.
+        request_id: Option<String>,
+    },
+    /// Error for a UI endpoint, such as opening a window in the game
+    /// client, returning a 404. This is almost always because the
+    /// character is not logged into a running game client.
+    #[error(
+        "The character is not logged into a running game client{}",
+        request_id_suffix(request_id)
+    )]
+    ClientNotRunning {
+        /// Value of the response's `X-ESI-Request-Id` header, if present.
+        request_id: Option<String>,
+    },
+    /// Error returned by EVE SSO when authenticating or refreshing
+    /// an access token, such as an expired authorization code, an
+    /// invalid client secret, or a refresh token that has been revoked.
+    #[error("SSO error '{error}': {description}")]
+    SsoError {
+        /// Error code, such as "invalid_grant".
+        error: String,
+        /// Human-readable description of the error.
+        description: String,
+    },
+    /// Error for SSO returning an access token of a type other than
+    /// "Bearer", which can't be used to authenticate requests to ESI.
+    #[error("Unexpected token type '{0}' from SSO; expected 'Bearer'")]
+    UnexpectedTokenType(String),
+    /// Error for the SSO token URL not being a valid URL with a host,
+    /// which is needed to know which SSO server to authenticate against.
+    #[error("Invalid SSO token URL '{0}'")]
+    InvalidTokenUrl(String),
     /// Error for if the provided user-agent header value has invalid characters.
     #[error("Invalid HTTP header value")]
     InvalidUserAgentHeader(#[from] http::header::InvalidHeaderValue),
@@ -48,15 +131,29 @@ pub enum EsiError {
     /// Error for not finding the passed operationId in the ESI Swagger spec.
     #[error("Could not resolve operationId '{0}' to a URL path")]
     UnknownOperationID(String),
+    /// Error for a path parameter of an endpoint not being given a value.
+    #[error("Missing value for path parameter '{0}'")]
+    MissingPathParameter(String),
     /// Error for being unable to parse the Swagger spec from ESI.
     #[error("Error occurred while parsing the Swagger spec at: {0}")]
     FailedSpecParse(String),
     /// Error for being unable to parse JSON from anywhere.
     #[error("Failed to serialize/deserialize JSON; this may be due to unexpected data or invalid struct field(s)")]
     FailedJsonParse(#[from] serde_json::Error),
+    /// Error for being unable to parse the YAML text of a notification.
+    #[cfg(feature = "notifications")]
+    #[error("Failed to parse notification text")]
+    FailedYamlParse(#[from] serde_yaml::Error),
+    /// Error for being unable to parse a date/time string from ESI.
+    #[error("Failed to parse date/time")]
+    FailedDateParse(#[from] chrono::ParseError),
     /// Error for being unable to get the current timestamp.
     #[error("Could not get current timestamp: {0}")]
     Timestamp(#[from] std::time::SystemTimeError),
+    /// Error for the clock set with `EsiBuilder::clock` returning a
+    /// time that can't be represented as a date.
+    #[error("Clock returned an out-of-range time: {0}")]
+    InvalidClockTime(i64),
     /// Error for the access token being used after expiring (and therefore
     /// being unable to be used for ESI) and no refresh token being present
     /// to fetch another access token.
@@ -66,7 +163,154 @@ pub enum EsiError {
     /// token could be found to refresh the access token
     #[error("No refresh token available to request an access token")]
     NoRefreshTokenAvailable,
+    /// Error for a response's `X-Pages` header not being a positive number.
+    #[error("Invalid page count '{0}' in the X-Pages header")]
+    InvalidPageCount(String),
+    /// Error for `RoutesGroup::get_route_avoiding_below` not finding a
+    /// route clear of systems below the given security, either because
+    /// ESI's limit of 100 avoided systems was reached or because ESI kept
+    /// routing through systems that were already avoided.
+    #[error("No route found that avoids systems below security {0}")]
+    NoRouteAvoiding(f64),
+    /// Error for a contact standing that ESI will not accept.
+    #[error("Invalid standing {0}; must be one of -10, -5, 0, 5, or 10")]
+    InvalidStanding(f64),
+    /// Error for a fitting item that ESI will not accept.
+    #[error("Invalid fitting item: {0}")]
+    InvalidFittingItem(String),
+}
+
+impl EsiError {
+    /// Build an `InvalidStatusCode` error from a failed response, or a
+    /// `NonJsonResponse` error if the response isn't JSON.
+    pub(crate) fn from_response(resp: &reqwest::Response) -> Self {
+        if let Some(content_type) = non_json_content_type(resp.headers()) {
+            return EsiError::NonJsonResponse {
+                status: resp.status().as_u16(),
+                content_type,
+                request_id: esi_request_id(resp.headers()),
+            };
+        }
+        EsiError::InvalidStatusCode {
+            status: resp.status().as_u16(),
+            request_id: esi_request_id(resp.headers()),
+        }
+    }
+
+    /// Build an error from a failed EVE SSO response.
+    ///
+    /// SSO describes the failure in a JSON body; if the body can't be
+    /// read or parsed, this falls back to an `InvalidStatusCode` error.
+    pub(crate) async fn from_sso_response(resp: reqwest::Response) -> Self {
+        let fallback = EsiError::from_response(&resp);
+        match resp.bytes().await {
+            Ok(body) => EsiError::from_sso_body(&body).unwrap_or(fallback),
+            Err(_) => fallback,
+        }
+    }
+
+    /// Parse the JSON body of a failed EVE SSO response.
+    fn from_sso_body(body: &[u8]) -> Option<Self> {
+        #[derive(serde::Deserialize)]
+        struct SsoErrorBody {
+            error: String,
+            #[serde(default)]
+            error_description: String,
+        }
+
+        let body: SsoErrorBody = serde_json::from_slice(body).ok()?;
+        Some(EsiError::SsoError {
+            error: body.error,
+            description: body.error_description,
+        })
+    }
+
+    /// Get the HTTP status code of the response that caused this error, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            EsiError::InvalidStatusCode { status, .. } => Some(*status),
+            EsiError::NonJsonResponse { status, .. } => Some(*status),
+            EsiError::Unauthorized { .. } => Some(401),
+            EsiError::Forbidden { .. } => Some(403),
+            EsiError::ClientNotRunning { .. } => Some(404),
+            _ => None,
+        }
+    }
+
+    /// Get the `X-ESI-Request-Id` of the response that caused this error, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            EsiError::InvalidStatusCode { request_id, .. }
+            | EsiError::NonJsonResponse { request_id, .. }
+            | EsiError::Unauthorized { request_id }
+            | EsiError::Forbidden { request_id }
+            | EsiError::ClientNotRunning { request_id } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Get the value of a response's `X-ESI-Request-Id` header.
+pub(crate) fn esi_request_id(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get("x-esi-request-id")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned())
+}
+
+/// Format a request ID for the end of an error message.
+fn request_id_suffix(request_id: &Option<String>) -> String {
+    request_id
+        .as_ref()
+        .map(|id| format!(" (request ID {id})"))
+        .unwrap_or_default()
+}
+
+/// Get the `Content-Type` of a response, if it is set to something other than JSON.
+pub(crate) fn non_json_content_type(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let content_type = headers.get(reqwest::header::CONTENT_TYPE)?.to_str().ok()?;
+    if content_type.contains("json") {
+        return None;
+    }
+    Some(content_type.to_owned())
 }
 
 /// Crate `Result` wrapper.
 pub type EsiResult<T> = Result<T, EsiError>;
+
+#[cfg(test)]
+mod tests {
+    use super::{non_json_content_type, EsiError};
+    use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+
+    #[test]
+    fn test_from_sso_body() {
+        let body = br#"{"error":"invalid_grant","error_description":"Invalid refresh token. Token missing/expired."}"#;
+        match EsiError::from_sso_body(body) {
+            Some(EsiError::SsoError { error, description }) => {
+                assert_eq!(error, "invalid_grant");
+                assert_eq!(description, "Invalid refresh token. Token missing/expired.");
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(EsiError::from_sso_body(b"<html>Bad Gateway</html>").is_none());
+    }
+
+    #[test]
+    fn test_non_json_content_type() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(non_json_content_type(&headers), None);
+
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/json; charset=UTF-8"),
+        );
+        assert_eq!(non_json_content_type(&headers), None);
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/html"));
+        assert_eq!(
+            non_json_content_type(&headers).as_deref(),
+            Some("text/html")
+        );
+    }
+}
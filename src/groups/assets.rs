@@ -1,11 +1,20 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+use crate::groups::Position;
 use crate::prelude::*;
+use crate::util::MAX_CONCURRENT_REQUESTS;
+
+/// Maximum number of item IDs accepted per asset names call.
+const MAX_IDS_PER_ASSET_NAMES_CALL: usize = 1_000;
 
 /// Endpoints for Assets
 pub struct AssetsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Asset {
     pub is_blueprint_copy: Option<bool>,
@@ -18,38 +27,124 @@ pub struct Asset {
     pub type_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
-#[allow(missing_docs)]
-pub struct AssetLocationPosition {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
+/// Whether a blueprint is an original or a copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum BlueprintKind {
+    /// A blueprint original, which can be copied and researched.
+    Original,
+    /// A blueprint copy, with a limited number of runs.
+    Copy,
 }
 
-#[derive(Debug, Deserialize)]
+impl Asset {
+    /// Get whether the asset is a blueprint original or copy, as far
+    /// as ESI says.
+    ///
+    /// ESI only includes `is_blueprint_copy` for blueprints, and may leave
+    /// it out for originals, so `None` means that the asset is either not
+    /// a blueprint or is an original. Use [`Asset::blueprint_kind_of`] or
+    /// [`AssetsGroup::blueprint_kinds`] to tell those apart.
+    pub fn blueprint_kind(&self) -> Option<BlueprintKind> {
+        self.is_blueprint_copy.map(|copy| {
+            if copy {
+                BlueprintKind::Copy
+            } else {
+                BlueprintKind::Original
+            }
+        })
+    }
+
+    /// Get whether the asset is a blueprint original or copy, given
+    /// whether its type is a blueprint.
+    ///
+    /// `None` if the type is not a blueprint.
+    pub fn blueprint_kind_of(&self, is_blueprint_type: bool) -> Option<BlueprintKind> {
+        is_blueprint_type.then(|| self.blueprint_kind().unwrap_or(BlueprintKind::Original))
+    }
+}
+
+/// Former name of [`Position`], which is now shared with the universe group.
+#[deprecated(note = "use `Position`")]
+pub type AssetLocationPosition = Position;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct AssetLocation {
     pub item_id: i64,
-    pub position: AssetLocationPosition,
+    pub position: Position,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct AssetName {
     pub item_id: u64,
     pub name: String,
 }
 
+/// An asset along with its custom name, if it has one.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamedAsset {
+    /// The asset.
+    pub asset: Asset,
+    /// The name given to the asset, if any.
+    pub name: Option<String>,
+}
+
 impl AssetsGroup<'_> {
     api_get!(
-        /// Get a character's assets.
+        /// Get a page of a character's assets.
         get_character_assets,
         "get_characters_character_id_assets",
         RequestType::Authenticated,
         Vec<Asset>,
-        (character_id: i32) => "{character_id}"
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
     );
 
+    /// Get all of a character's assets, fetching every page.
+    pub async fn get_all_character_assets(&self, character_id: i32) -> EsiResult<Vec<Asset>> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_characters_character_id_assets")?
+            .replace("{character_id}", &character_id.to_string());
+        self.esi
+            .query_all_pages("GET", RequestType::Authenticated, &path, None)
+            .await
+    }
+
+    /// Get a character's assets that are directly in a location.
+    ///
+    /// ESI has no filter for this, so every page of assets is fetched
+    /// and filtered here. Items inside containers or ships have the
+    /// container's `item_id` as their `location_id`.
+    pub async fn assets_at_location(
+        &self,
+        character_id: i32,
+        location_id: i64,
+    ) -> EsiResult<Vec<Asset>> {
+        let assets = self.get_all_character_assets(character_id).await?;
+        Ok(assets
+            .into_iter()
+            .filter(|asset| asset.location_id == location_id)
+            .collect())
+    }
+
+    /// Get a character's assets of a type.
+    ///
+    /// ESI has no filter for this, so every page of assets is fetched
+    /// and filtered here.
+    pub async fn assets_of_type(&self, character_id: i32, type_id: i32) -> EsiResult<Vec<Asset>> {
+        let assets = self.get_all_character_assets(character_id).await?;
+        Ok(assets
+            .into_iter()
+            .filter(|asset| asset.type_id == type_id)
+            .collect())
+    }
+
     api_post!(
         /// Get locations of some of a character's assets.
         get_character_assets_locations,
@@ -70,15 +165,76 @@ impl AssetsGroup<'_> {
         item_ids: &[u64],
     );
 
+    /// Annotate a character's assets with their custom names.
+    ///
+    /// Only singleton (assembled) items can be named, so only those are
+    /// looked up, in chunks of at most 1,000 IDs. The returned list is in
+    /// the same order as `assets`.
+    pub async fn get_named_assets(
+        &self,
+        character_id: i32,
+        assets: &[Asset],
+    ) -> EsiResult<Vec<NamedAsset>> {
+        let item_ids: Vec<u64> = assets
+            .iter()
+            .filter(|asset| asset.is_singleton)
+            .map(|asset| asset.item_id as u64)
+            .collect();
+        let mut names = HashMap::new();
+        for chunk in item_ids.chunks(MAX_IDS_PER_ASSET_NAMES_CALL) {
+            let chunk_names = self.get_character_assets_names(character_id, chunk).await?;
+            names.extend(chunk_names.into_iter().map(|n| (n.item_id, n.name)));
+        }
+        Ok(assets
+            .iter()
+            .map(|asset| NamedAsset {
+                asset: asset.clone(),
+                name: names.remove(&(asset.item_id as u64)),
+            })
+            .collect())
+    }
+
+    /// Get whether each asset is a blueprint original or copy, or `None`
+    /// if it isn't a blueprint.
+    ///
+    /// Whether each distinct type is a blueprint is looked up with
+    /// [`UniverseGroup::is_blueprint_type`], concurrently with a bounded
+    /// number of requests in flight. The results are in the same order
+    /// as `assets`.
+    ///
+    /// [`UniverseGroup::is_blueprint_type`]: crate::groups::UniverseGroup::is_blueprint_type
+    pub async fn blueprint_kinds(&self, assets: &[Asset]) -> EsiResult<Vec<Option<BlueprintKind>>> {
+        let mut type_ids: Vec<i32> = assets.iter().map(|asset| asset.type_id).collect();
+        type_ids.sort_unstable();
+        type_ids.dedup();
+        let universe = self.esi.group_universe();
+        let blueprint_types: HashMap<i32, bool> =
+            stream::iter(type_ids)
+                .map(|type_id| {
+                    let universe = &universe;
+                    async move {
+                        Ok::<_, EsiError>((type_id, universe.is_blueprint_type(type_id).await?))
+                    }
+                })
+                .buffered(MAX_CONCURRENT_REQUESTS)
+                .try_collect()
+                .await?;
+        Ok(assets
+            .iter()
+            .map(|asset| asset.blueprint_kind_of(blueprint_types[&asset.type_id]))
+            .collect())
+    }
+
     api_get!(
-        /// Get a corporation's assets.
+        /// Get a page of a corporation's assets.
         ///
         /// Requires the auth'd character to be a director/+ in the corp.
         get_corporation_assets,
         "get_corporations_corporation_id_assets",
         RequestType::Authenticated,
         Vec<Asset>,
-        (corporation_id: u64) => "{corporation_id}"
+        (corporation_id: u64) => "{corporation_id}";
+        Optional(page: i32) => "page"
     );
 
     api_post!(
@@ -105,3 +261,43 @@ impl AssetsGroup<'_> {
         item_ids: &[u64],
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Asset, BlueprintKind};
+
+    #[test]
+    fn test_blueprint_kind() {
+        let source = r#"[
+            {
+              "is_blueprint_copy": true,
+              "is_singleton": true,
+              "item_id": 1000000000001,
+              "location_flag": "Hangar",
+              "location_id": 60003760,
+              "location_type": "station",
+              "quantity": -2,
+              "type_id": 691
+            },
+            {
+              "is_singleton": true,
+              "item_id": 1000000000002,
+              "location_flag": "Hangar",
+              "location_id": 60003760,
+              "location_type": "station",
+              "quantity": -1,
+              "type_id": 691
+            }
+          ]"#;
+        let assets: Vec<Asset> = serde_json::from_str(source).unwrap();
+
+        assert_eq!(assets[0].blueprint_kind(), Some(BlueprintKind::Copy));
+        assert_eq!(assets[0].blueprint_kind_of(true), Some(BlueprintKind::Copy));
+        assert_eq!(assets[1].blueprint_kind(), None);
+        assert_eq!(
+            assets[1].blueprint_kind_of(true),
+            Some(BlueprintKind::Original)
+        );
+        assert_eq!(assets[1].blueprint_kind_of(false), None);
+    }
+}
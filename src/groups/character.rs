@@ -1,11 +1,16 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::groups::{corporation::roles_include, CorpRole};
 use crate::prelude::*;
+use crate::util::parse_datetime;
 
 /// Endpoints for Character
 pub struct CharacterGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterPublicInfo {
     pub alliance_id: Option<i32>,
@@ -20,7 +25,8 @@ pub struct CharacterPublicInfo {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterCorporationHistoryItem {
     pub corporation_id: i32,
@@ -29,7 +35,54 @@ pub struct CharacterCorporationHistoryItem {
     pub start_date: String,
 }
 
-#[derive(Debug, Deserialize)]
+/// A character's time in one corporation, from their corporation history.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CorporationTenure {
+    /// ID of the corporation.
+    pub corporation_id: i32,
+    /// Whether the corporation has since been closed.
+    pub is_deleted: bool,
+    /// ID of the history record.
+    pub record_id: i32,
+    /// When the character joined the corporation.
+    pub start_date: DateTime<Utc>,
+    /// When the character left the corporation, which is when they joined
+    /// the next one. `None` if this is their current corporation.
+    pub end_date: Option<DateTime<Utc>>,
+    /// How long the character was in the corporation. `None` if this is
+    /// their current corporation.
+    pub duration: Option<Duration>,
+}
+
+impl CorporationTenure {
+    /// Build the tenure of each entry in a character's corporation
+    /// history, sorted from oldest to newest.
+    pub fn from_history(history: &[CharacterCorporationHistoryItem]) -> EsiResult<Vec<Self>> {
+        let mut tenures = history
+            .iter()
+            .map(|item| {
+                Ok(CorporationTenure {
+                    corporation_id: item.corporation_id,
+                    is_deleted: item.is_deleted.unwrap_or(false),
+                    record_id: item.record_id,
+                    start_date: parse_datetime(&item.start_date)?,
+                    end_date: None,
+                    duration: None,
+                })
+            })
+            .collect::<EsiResult<Vec<_>>>()?;
+        tenures.sort_by_key(|tenure| (tenure.start_date, tenure.record_id));
+        let starts: Vec<DateTime<Utc>> = tenures.iter().map(|tenure| tenure.start_date).collect();
+        for (tenure, next_start) in tenures.iter_mut().zip(starts.into_iter().skip(1)) {
+            tenure.end_date = Some(next_start);
+            tenure.duration = Some(next_start - tenure.start_date);
+        }
+        Ok(tenures)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterPortraitInfo {
     pub px128x128: Option<String>,
@@ -38,7 +91,8 @@ pub struct CharacterPortraitInfo {
     pub px64x64: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterAffiliation {
     pub alliance_id: Option<i32>,
@@ -47,7 +101,8 @@ pub struct CharacterAffiliation {
     pub faction_id: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Blueprint {
     pub item_id: i64,
@@ -60,21 +115,289 @@ pub struct Blueprint {
     pub type_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+/// A character's roles in their corporation.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CharacterRoles {
+    pub roles: Option<Vec<CorpRole>>,
+    pub roles_at_base: Option<Vec<CorpRole>>,
+    pub roles_at_hq: Option<Vec<CorpRole>>,
+    pub roles_at_other: Option<Vec<CorpRole>>,
+}
+
+impl CharacterRoles {
+    /// Whether the character holds a role globally.
+    ///
+    /// Directors are treated as holding every role.
+    pub fn has_role(&self, role: &CorpRole) -> bool {
+        roles_include(self.roles.as_deref(), role)
+    }
+}
+
+/// Type of a character notification.
+///
+/// Values that ESI adds after this list was written are kept as `Unknown`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub enum NotificationType {
+    AcceptedAlly,
+    AcceptedSurrender,
+    AgentRetiredTrigravian,
+    AllAnchoringMsg,
+    AllMaintenanceBillMsg,
+    AllStrucInvulnerableMsg,
+    AllStructVulnerableMsg,
+    AllWarCorpJoinedAllianceMsg,
+    AllWarDeclaredMsg,
+    AllWarInvalidatedMsg,
+    AllWarRetractedMsg,
+    AllWarSurrenderMsg,
+    AllianceCapitalChanged,
+    AllianceWarDeclaredV2,
+    AllyContractCancelled,
+    AllyJoinedWarAggressorMsg,
+    AllyJoinedWarAllyMsg,
+    AllyJoinedWarDefenderMsg,
+    BattlePunishFriendlyFire,
+    BillOutOfMoneyMsg,
+    BillPaidCorpAllMsg,
+    BountyClaimMsg,
+    BountyESSShared,
+    BountyESSTaken,
+    BountyPlacedAlliance,
+    BountyPlacedChar,
+    BountyPlacedCorp,
+    BountyYourBountyClaimed,
+    BuddyConnectContactAdd,
+    CharAppAcceptMsg,
+    CharAppRejectMsg,
+    CharAppWithdrawMsg,
+    CharLeftCorpMsg,
+    CharMedalMsg,
+    CharTerminationMsg,
+    CloneActivationMsg,
+    CloneActivationMsg2,
+    CloneMovedMsg,
+    CloneRevokedMsg1,
+    CloneRevokedMsg2,
+    CombatOperationFinished,
+    ContactAdd,
+    ContactEdit,
+    ContainerPasswordMsg,
+    ContractRegionChangedToPochven,
+    CorpAllBillMsg,
+    CorpAppAcceptMsg,
+    CorpAppInvitedMsg,
+    CorpAppNewMsg,
+    CorpAppRejectCustomMsg,
+    CorpAppRejectMsg,
+    CorpBecameWarEligible,
+    CorpDividendMsg,
+    CorpFriendlyFireDisableTimerCompleted,
+    CorpFriendlyFireDisableTimerStarted,
+    CorpFriendlyFireEnableTimerCompleted,
+    CorpFriendlyFireEnableTimerStarted,
+    CorpKicked,
+    CorpLiquidationMsg,
+    CorpNewCEOMsg,
+    CorpNewsMsg,
+    CorpNoLongerWarEligible,
+    CorpOfficeExpirationMsg,
+    CorpStructLostMsg,
+    CorpTaxChangeMsg,
+    CorpVoteCEORevokedMsg,
+    CorpVoteMsg,
+    CorpWarDeclaredMsg,
+    CorpWarDeclaredV2,
+    CorpWarFightingLegalMsg,
+    CorpWarInvalidatedMsg,
+    CorpWarRetractedMsg,
+    CorpWarSurrenderMsg,
+    CustomsMsg,
+    DeclareWar,
+    DistrictAttacked,
+    DustAppAcceptedMsg,
+    ESSMainBankLink,
+    EntosisCaptureStarted,
+    ExpertSystemExpired,
+    ExpertSystemExpiryImminent,
+    FWAllianceKickMsg,
+    FWAllianceWarningMsg,
+    FWCharKickMsg,
+    FWCharRankGainMsg,
+    FWCharRankLossMsg,
+    FWCharWarningMsg,
+    FWCorpJoinMsg,
+    FWCorpKickMsg,
+    FWCorpLeaveMsg,
+    FWCorpWarningMsg,
+    FacWarCorpJoinRequestMsg,
+    FacWarCorpJoinWithdrawMsg,
+    FacWarCorpLeaveRequestMsg,
+    FacWarCorpLeaveWithdrawMsg,
+    FacWarLPDisqualifiedEvent,
+    FacWarLPDisqualifiedKill,
+    FacWarLPPayoutEvent,
+    FacWarLPPayoutKill,
+    GameTimeAdded,
+    GameTimeReceived,
+    GameTimeSent,
+    GiftReceived,
+    IHubDestroyedByBillFailure,
+    IncursionCompletedMsg,
+    IndustryOperationFinished,
+    IndustryTeamAuctionLost,
+    IndustryTeamAuctionWon,
+    InfrastructureHubBillAboutToExpire,
+    InsuranceExpirationMsg,
+    InsuranceFirstShipMsg,
+    InsuranceInvalidatedMsg,
+    InsuranceIssuedMsg,
+    InsurancePayoutMsg,
+    InvasionCompletedMsg,
+    InvasionSystemLogin,
+    InvasionSystemStart,
+    JumpCloneDeletedMsg1,
+    JumpCloneDeletedMsg2,
+    KillReportFinalBlow,
+    KillReportVictim,
+    KillRightAvailable,
+    KillRightAvailableOpen,
+    KillRightEarned,
+    KillRightUnavailable,
+    KillRightUnavailableOpen,
+    KillRightUsed,
+    LocateCharMsg,
+    MadeWarMutual,
+    MercOfferRetractedMsg,
+    MercOfferedNegotiationMsg,
+    MissionCanceledTriglavian,
+    MissionOfferExpirationMsg,
+    MissionTimeoutMsg,
+    MoonminingAutomaticFracture,
+    MoonminingExtractionCancelled,
+    MoonminingExtractionFinished,
+    MoonminingExtractionStarted,
+    MoonminingLaserFired,
+    MutualWarExpired,
+    MutualWarInviteAccepted,
+    MutualWarInviteRejected,
+    MutualWarInviteSent,
+    NPCStandingsGained,
+    NPCStandingsLost,
+    OfferToAllyRetracted,
+    OfferedSurrender,
+    OfferedToAlly,
+    OfficeLeaseCanceledInsufficientStandings,
+    OldLscMessages,
+    OperationFinished,
+    OrbitalAttacked,
+    OrbitalReinforced,
+    OwnershipTransferred,
+    RaffleCreated,
+    RaffleExpired,
+    RaffleFinished,
+    ReimbursementMsg,
+    ResearchMissionAvailableMsg,
+    RetractsWar,
+    SeasonalChallengeCompleted,
+    SovAllClaimAquiredMsg,
+    SovAllClaimLostMsg,
+    SovCommandNodeEventStarted,
+    SovCorpBillLateMsg,
+    SovCorpClaimFailMsg,
+    SovDisruptorMsg,
+    SovStationEnteredFreeport,
+    SovStructureDestroyed,
+    SovStructureReinforced,
+    SovStructureSelfDestructCancel,
+    SovStructureSelfDestructFinished,
+    SovStructureSelfDestructRequested,
+    SovereigntyIHDamageMsg,
+    SovereigntySBUAbandonMsg,
+    SovereigntyTCUDamageMsg,
+    StationAggressionMsg1,
+    StationAggressionMsg2,
+    StationConquerMsg,
+    StationServiceDisabled,
+    StationServiceEnabled,
+    StationStateChangeMsg,
+    StoryLineMissionAvailableMsg,
+    StructureAnchoring,
+    StructureCourierContractChanged,
+    StructureDestroyed,
+    StructureFuelAlert,
+    StructureImpendingAbandonmentAssetsAtRisk,
+    StructureItemsDelivered,
+    StructureItemsMovedToSafety,
+    StructureLostArmor,
+    StructureLostShields,
+    StructureOnline,
+    StructureServicesOffline,
+    StructureUnanchoring,
+    StructureUnderAttack,
+    StructureWentHighPower,
+    StructureWentLowPower,
+    StructuresJobsCancelled,
+    StructuresJobsPaused,
+    StructuresReinforcementChanged,
+    TowerAlertMsg,
+    TowerResourceAlertMsg,
+    TransactionReversalMsg,
+    TutorialMsg,
+    WarAdopted,
+    WarAllyInherited,
+    WarAllyOfferDeclinedMsg,
+    WarConcordInvalidates,
+    WarDeclared,
+    WarEndedHqSecurityDrop,
+    WarHQRemovedFromSpace,
+    WarInherited,
+    WarInvalid,
+    WarRetracted,
+    WarRetractedByConcord,
+    WarSurrenderDeclinedMsg,
+    WarSurrenderOfferMsg,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// Type of the entity that sent a notification.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[allow(missing_docs)]
+pub enum SenderType {
+    Character,
+    Corporation,
+    Alliance,
+    Faction,
+    Other,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Notification {
     #[serde(default)]
     pub is_read: bool,
     pub notification_id: i64,
     pub sender_id: i32,
-    pub sender_type: String,
+    pub sender_type: SenderType,
     pub text: Option<String>,
     pub timestamp: String,
     #[serde(rename = "type")]
-    pub notification_type: String,
+    pub notification_type: NotificationType,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct WalletTransaction {
     pub client_id: i32,
@@ -101,6 +424,9 @@ impl CharacterGroup<'_> {
 
     api_get!(
         /// Get a character's corporation history.
+        ///
+        /// See [`CorporationTenure::from_history`] for how long the
+        /// character spent in each corporation.
         get_history,
         "get_characters_character_id_corporationhistory",
         RequestType::Public,
@@ -128,11 +454,21 @@ impl CharacterGroup<'_> {
     );
 
     api_get!(
-        /// Get character blueprints.
+        /// Get a page of a character's blueprints.
         get_blueprints,
         "get_characters_character_id_blueprints",
         RequestType::Authenticated,
         Vec<Blueprint>,
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
+    );
+
+    api_get!(
+        /// Get a character's roles in their corporation.
+        get_roles,
+        "get_characters_character_id_roles",
+        RequestType::Authenticated,
+        CharacterRoles,
         (character_id: i32) => "{character_id}"
     );
 
@@ -154,3 +490,70 @@ impl CharacterGroup<'_> {
         (character_id: i32) => "{character_id}"
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CharacterCorporationHistoryItem, CorporationTenure, Notification, NotificationType,
+        SenderType,
+    };
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_notification_deserialize() {
+        let source = r#"[
+            {
+                "notification_id": 1,
+                "sender_id": 1000137,
+                "sender_type": "corporation",
+                "text": "solarsystemID: 30000142\n",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "type": "StructureUnderAttack"
+            },
+            {
+                "is_read": true,
+                "notification_id": 2,
+                "sender_id": 1,
+                "sender_type": "something_else",
+                "timestamp": "2024-01-01T00:00:00Z",
+                "type": "SomeNewNotification"
+            }
+        ]"#;
+        let notifications: Vec<Notification> = serde_json::from_str(source).unwrap();
+        assert_eq!(
+            notifications[0].notification_type,
+            NotificationType::StructureUnderAttack
+        );
+        assert_eq!(notifications[0].sender_type, SenderType::Corporation);
+        assert_eq!(
+            notifications[1].notification_type,
+            NotificationType::Unknown("SomeNewNotification".to_owned())
+        );
+        assert_eq!(
+            notifications[1].sender_type,
+            SenderType::Unknown("something_else".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_corporation_tenures() {
+        let source = r#"[
+            { "corporation_id": 98000002, "record_id": 3, "start_date": "2024-03-01T00:00:00Z" },
+            { "corporation_id": 98000001, "is_deleted": true, "record_id": 2, "start_date": "2024-01-11T12:00:00Z" },
+            { "corporation_id": 1000167, "record_id": 1, "start_date": "2024-01-01T12:00:00Z" }
+        ]"#;
+        let history: Vec<CharacterCorporationHistoryItem> = serde_json::from_str(source).unwrap();
+        let tenures = CorporationTenure::from_history(&history).unwrap();
+
+        let ids: Vec<i32> = tenures.iter().map(|t| t.corporation_id).collect();
+        assert_eq!(ids, vec![1000167, 98000001, 98000002]);
+        assert_eq!(tenures[0].duration, Some(Duration::days(10)));
+        assert!(tenures[1].is_deleted);
+        assert_eq!(
+            tenures[1].end_date,
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(tenures[2].end_date, None);
+        assert_eq!(tenures[2].duration, None);
+    }
+}
@@ -1,8 +1,266 @@
-#![allow(unused)]
+use std::{collections::HashSet, fmt};
 
 use crate::prelude::*;
 
+/// Standing values that ESI accepts when adding or editing contacts.
+const VALID_STANDINGS: [f64; 5] = [-10.0, -5.0, 0.0, 5.0, 10.0];
+
+/// Maximum number of contact IDs accepted per add/edit call.
+const MAX_CONTACTS_PER_CALL: usize = 100;
+
 /// Endpoints for Contacts
 pub struct ContactsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+/// A standing that ESI accepts when adding or editing contacts.
+///
+/// Only -10, -5, 0, 5 and 10 are accepted; any other value is
+/// rejected by ESI, so it is rejected here instead.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize)]
+pub struct Standing(f64);
+
+impl Standing {
+    /// Terrible standing (-10).
+    pub const TERRIBLE: Standing = Standing(-10.0);
+    /// Bad standing (-5).
+    pub const BAD: Standing = Standing(-5.0);
+    /// Neutral standing (0).
+    pub const NEUTRAL: Standing = Standing(0.0);
+    /// Good standing (5).
+    pub const GOOD: Standing = Standing(5.0);
+    /// Excellent standing (10).
+    pub const EXCELLENT: Standing = Standing(10.0);
+
+    /// Create a standing, returning `None` if ESI wouldn't accept the value.
+    pub fn new(value: f64) -> Option<Standing> {
+        VALID_STANDINGS.contains(&value).then_some(Standing(value))
+    }
+
+    /// Get the standing's value.
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for Standing {
+    type Error = EsiError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Standing::new(value).ok_or(EsiError::InvalidStanding(value))
+    }
+}
+
+impl fmt::Display for Standing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct Contact {
+    pub contact_id: i32,
+    pub contact_type: String,
+    pub is_blocked: Option<bool>,
+    pub is_watched: Option<bool>,
+    pub label_ids: Option<Vec<i64>>,
+    pub standing: f64,
+}
+
+/// Result of bulk-setting standings with
+/// [`ContactsGroup::set_character_standings`].
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StandingsUpdate {
+    /// IDs of contacts that were newly added.
+    pub created: Vec<i32>,
+    /// IDs of existing contacts that had their standing changed.
+    pub updated: Vec<i32>,
+}
+
+impl ContactsGroup<'_> {
+    api_get!(
+        /// Get a character's contacts.
+        get_character_contacts,
+        "get_characters_character_id_contacts",
+        RequestType::Authenticated,
+        Vec<Contact>,
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
+    );
+
+    api_get_paged!(
+        /// Get a page of a character's contacts, with the total page count.
+        get_character_contacts_paged,
+        "get_characters_character_id_contacts",
+        RequestType::Authenticated,
+        Contact,
+        (character_id: i32) => "{character_id}"
+    );
+
+    /// Add contacts to a character's contact list, all with the same standing.
+    ///
+    /// Returns the IDs of the created contacts. ESI accepts
+    /// at most 100 contact IDs per call.
+    pub async fn add_character_contacts(
+        &self,
+        character_id: i32,
+        contact_ids: &[i32],
+        standing: Standing,
+    ) -> EsiResult<Vec<i32>> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("post_characters_character_id_contacts")?
+            .replace("{character_id}", &character_id.to_string());
+        let standing = standing.to_string();
+        let body = serde_json::to_string(contact_ids)?;
+        self.esi
+            .query(
+                "POST",
+                RequestType::Authenticated,
+                &path,
+                Some(&[("standing", &standing)]),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Edit the standing of contacts on a character's contact list.
+    ///
+    /// ESI accepts at most 100 contact IDs per call.
+    pub async fn edit_character_contacts(
+        &self,
+        character_id: i32,
+        contact_ids: &[i32],
+        standing: Standing,
+    ) -> EsiResult<()> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("put_characters_character_id_contacts")?
+            .replace("{character_id}", &character_id.to_string());
+        let standing = standing.to_string();
+        let body = serde_json::to_string(contact_ids)?;
+        self.esi
+            .query(
+                "PUT",
+                RequestType::Authenticated,
+                &path,
+                Some(&[("standing", &standing)]),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Set the standings of many contacts at once.
+    ///
+    /// Entries are deduplicated by contact ID (the last entry wins) and
+    /// grouped by standing, then sent in chunks of at most 100 IDs. Contacts
+    /// that are already on the character's list are edited; the rest are added.
+    pub async fn set_character_standings(
+        &self,
+        character_id: i32,
+        entries: &[(i32, Standing)],
+    ) -> EsiResult<StandingsUpdate> {
+        let existing: HashSet<i32> = self
+            .esi
+            .query_all_pages::<Contact>(
+                "GET",
+                RequestType::Authenticated,
+                &self
+                    .esi
+                    .get_wrapped_endpoint("get_characters_character_id_contacts")?
+                    .replace("{character_id}", &character_id.to_string()),
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|contact| contact.contact_id)
+            .collect();
+
+        let mut update = StandingsUpdate::default();
+        for (standing, to_add, to_edit) in group_standings(entries, &existing) {
+            for chunk in to_add.chunks(MAX_CONTACTS_PER_CALL) {
+                let created = self
+                    .add_character_contacts(character_id, chunk, standing)
+                    .await?;
+                update.created.extend(created);
+            }
+            for chunk in to_edit.chunks(MAX_CONTACTS_PER_CALL) {
+                self.edit_character_contacts(character_id, chunk, standing)
+                    .await?;
+                update.updated.extend_from_slice(chunk);
+            }
+        }
+        Ok(update)
+    }
+}
+
+/// Deduplicate entries by ID and group them by standing, split into
+/// IDs to add and IDs to edit.
+fn group_standings(
+    entries: &[(i32, Standing)],
+    existing: &HashSet<i32>,
+) -> Vec<(Standing, Vec<i32>, Vec<i32>)> {
+    let mut seen = HashSet::new();
+    let mut deduped: Vec<(i32, Standing)> = entries
+        .iter()
+        .rev()
+        .filter(|(id, _)| seen.insert(*id))
+        .copied()
+        .collect();
+    deduped.reverse();
+
+    VALID_STANDINGS
+        .iter()
+        .map(|&value| {
+            let standing = Standing(value);
+            let (to_edit, to_add): (Vec<i32>, Vec<i32>) = deduped
+                .iter()
+                .filter(|(_, s)| *s == standing)
+                .map(|(id, _)| *id)
+                .partition(|id| existing.contains(id));
+            (standing, to_add, to_edit)
+        })
+        .filter(|(_, to_add, to_edit)| !to_add.is_empty() || !to_edit.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_standings, Standing};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_standing_new() {
+        assert_eq!(Standing::new(-10.0), Some(Standing::TERRIBLE));
+        assert_eq!(Standing::new(5.0), Some(Standing::GOOD));
+        assert_eq!(Standing::new(2.5), None);
+        assert_eq!(Standing::new(11.0), None);
+        assert!(Standing::try_from(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_group_standings_dedup() {
+        let existing = HashSet::from([2]);
+        let groups = group_standings(
+            &[
+                (1, Standing::GOOD),
+                (2, Standing::GOOD),
+                (3, Standing::TERRIBLE),
+                (1, Standing::EXCELLENT),
+            ],
+            &existing,
+        );
+
+        assert_eq!(
+            groups,
+            vec![
+                (Standing::TERRIBLE, vec![3], vec![]),
+                (Standing::GOOD, vec![], vec![2]),
+                (Standing::EXCELLENT, vec![1], vec![]),
+            ]
+        );
+    }
+}
@@ -1,6 +1,10 @@
+use chrono::{DateTime, Duration, Utc};
+
 use crate::prelude::*;
+use crate::util::parse_datetime;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CorporationPublicInfo {
     pub alliance_id: Option<i32>,
@@ -19,7 +23,8 @@ pub struct CorporationPublicInfo {
     pub war_eligible: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CorporationHistoryItem {
     pub alliance_id: Option<i32>,
@@ -28,6 +33,332 @@ pub struct CorporationHistoryItem {
     pub start_date: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorporationMemberName {
+    pub character_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorporationDivision {
+    pub division: Option<i32>,
+    pub name: Option<String>,
+}
+
+/// Names of a corporation's hangar and wallet divisions, numbered 1 to 7.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorporationDivisions {
+    pub hangar: Option<Vec<CorporationDivision>>,
+    pub wallet: Option<Vec<CorporationDivision>>,
+}
+
+/// State of a corporation-owned structure.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[allow(missing_docs)]
+pub enum CorpStructureState {
+    AnchorVulnerable,
+    Anchoring,
+    ArmorReinforce,
+    ArmorVulnerable,
+    DeployVulnerable,
+    FittingInvulnerable,
+    HullReinforce,
+    HullVulnerable,
+    OnlineDeprecated,
+    OnliningVulnerable,
+    ShieldVulnerable,
+    Unanchored,
+    /// ESI's own "unknown" state.
+    Unknown,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Other(String),
+}
+
+impl CorpStructureState {
+    /// Whether the structure is in a reinforcement timer.
+    pub fn is_reinforced(&self) -> bool {
+        matches!(
+            self,
+            CorpStructureState::ArmorReinforce | CorpStructureState::HullReinforce
+        )
+    }
+
+    /// Whether the structure can currently be attacked.
+    pub fn is_vulnerable(&self) -> bool {
+        matches!(
+            self,
+            CorpStructureState::AnchorVulnerable
+                | CorpStructureState::ArmorVulnerable
+                | CorpStructureState::DeployVulnerable
+                | CorpStructureState::HullVulnerable
+                | CorpStructureState::OnliningVulnerable
+                | CorpStructureState::ShieldVulnerable
+        )
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorpStructureService {
+    pub name: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorpStructure {
+    pub corporation_id: i32,
+    pub fuel_expires: Option<String>,
+    pub name: Option<String>,
+    pub next_reinforce_apply: Option<String>,
+    pub next_reinforce_hour: Option<i32>,
+    pub profile_id: i32,
+    pub reinforce_hour: Option<i32>,
+    pub services: Option<Vec<CorpStructureService>>,
+    pub state: CorpStructureState,
+    pub state_timer_end: Option<String>,
+    pub state_timer_start: Option<String>,
+    pub structure_id: i64,
+    pub system_id: i32,
+    pub type_id: i32,
+    pub unanchors_at: Option<String>,
+}
+
+impl CorpStructure {
+    /// Get how long until the structure runs out of fuel.
+    ///
+    /// Negative if the fuel has already run out. `None` if the structure
+    /// has no fuel expiry (such as when it has no services online).
+    ///
+    /// `now` is usually [`Esi::now`].
+    pub fn fuel_expires_in(&self, now: DateTime<Utc>) -> EsiResult<Option<Duration>> {
+        Ok(self
+            .fuel_expires
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()?
+            .map(|expires| expires - now))
+    }
+
+    /// Get when the structure's current state timer ends, such as
+    /// when it comes out of reinforcement.
+    pub fn state_timer_end(&self) -> EsiResult<Option<DateTime<Utc>>> {
+        self.state_timer_end
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct MemberTracking {
+    pub base_id: Option<i32>,
+    pub character_id: i32,
+    pub location_id: Option<i64>,
+    pub logoff_date: Option<String>,
+    pub logon_date: Option<String>,
+    pub ship_type_id: Option<i32>,
+    pub start_date: Option<String>,
+}
+
+impl MemberTracking {
+    /// Get the number of whole days since the member last logged in.
+    ///
+    /// `None` if ESI has no record of the member logging in.
+    ///
+    /// `now` is usually [`Esi::now`].
+    pub fn days_since_logon(&self, now: DateTime<Utc>) -> EsiResult<Option<i64>> {
+        Ok(self
+            .logon_date
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()?
+            .map(|logon| (now - logon).num_days()))
+    }
+
+    /// Get whether the member hasn't logged in for at least `threshold_days`.
+    ///
+    /// A member that ESI has no record of logging in is counted as inactive.
+    ///
+    /// `now` is usually [`Esi::now`].
+    pub fn likely_inactive(&self, threshold_days: i64, now: DateTime<Utc>) -> EsiResult<bool> {
+        Ok(self
+            .days_since_logon(now)?
+            .is_none_or(|days| days >= threshold_days))
+    }
+}
+
+/// A corporation role, as held or granted by a corporation member.
+///
+/// Values that ESI adds after this list was written are kept as `Unknown`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub enum CorpRole {
+    #[serde(rename = "Account_Take_1")]
+    AccountTake1,
+    #[serde(rename = "Account_Take_2")]
+    AccountTake2,
+    #[serde(rename = "Account_Take_3")]
+    AccountTake3,
+    #[serde(rename = "Account_Take_4")]
+    AccountTake4,
+    #[serde(rename = "Account_Take_5")]
+    AccountTake5,
+    #[serde(rename = "Account_Take_6")]
+    AccountTake6,
+    #[serde(rename = "Account_Take_7")]
+    AccountTake7,
+    #[serde(rename = "Accountant")]
+    Accountant,
+    #[serde(rename = "Auditor")]
+    Auditor,
+    #[serde(rename = "Brand_Manager")]
+    BrandManager,
+    #[serde(rename = "Communications_Officer")]
+    CommunicationsOfficer,
+    #[serde(rename = "Config_Equipment")]
+    ConfigEquipment,
+    #[serde(rename = "Config_Starbase_Equipment")]
+    ConfigStarbaseEquipment,
+    #[serde(rename = "Container_Take_1")]
+    ContainerTake1,
+    #[serde(rename = "Container_Take_2")]
+    ContainerTake2,
+    #[serde(rename = "Container_Take_3")]
+    ContainerTake3,
+    #[serde(rename = "Container_Take_4")]
+    ContainerTake4,
+    #[serde(rename = "Container_Take_5")]
+    ContainerTake5,
+    #[serde(rename = "Container_Take_6")]
+    ContainerTake6,
+    #[serde(rename = "Container_Take_7")]
+    ContainerTake7,
+    #[serde(rename = "Contract_Manager")]
+    ContractManager,
+    #[serde(rename = "Deliveries_Container_Take")]
+    DeliveriesContainerTake,
+    #[serde(rename = "Deliveries_Query")]
+    DeliveriesQuery,
+    #[serde(rename = "Deliveries_Take")]
+    DeliveriesTake,
+    #[serde(rename = "Diplomat")]
+    Diplomat,
+    #[serde(rename = "Director")]
+    Director,
+    #[serde(rename = "Factory_Manager")]
+    FactoryManager,
+    #[serde(rename = "Fitting_Manager")]
+    FittingManager,
+    #[serde(rename = "Hangar_Query_1")]
+    HangarQuery1,
+    #[serde(rename = "Hangar_Query_2")]
+    HangarQuery2,
+    #[serde(rename = "Hangar_Query_3")]
+    HangarQuery3,
+    #[serde(rename = "Hangar_Query_4")]
+    HangarQuery4,
+    #[serde(rename = "Hangar_Query_5")]
+    HangarQuery5,
+    #[serde(rename = "Hangar_Query_6")]
+    HangarQuery6,
+    #[serde(rename = "Hangar_Query_7")]
+    HangarQuery7,
+    #[serde(rename = "Hangar_Take_1")]
+    HangarTake1,
+    #[serde(rename = "Hangar_Take_2")]
+    HangarTake2,
+    #[serde(rename = "Hangar_Take_3")]
+    HangarTake3,
+    #[serde(rename = "Hangar_Take_4")]
+    HangarTake4,
+    #[serde(rename = "Hangar_Take_5")]
+    HangarTake5,
+    #[serde(rename = "Hangar_Take_6")]
+    HangarTake6,
+    #[serde(rename = "Hangar_Take_7")]
+    HangarTake7,
+    #[serde(rename = "Junior_Accountant")]
+    JuniorAccountant,
+    #[serde(rename = "Personnel_Manager")]
+    PersonnelManager,
+    #[serde(rename = "Project_Manager")]
+    ProjectManager,
+    #[serde(rename = "Rent_Factory_Facility")]
+    RentFactoryFacility,
+    #[serde(rename = "Rent_Office")]
+    RentOffice,
+    #[serde(rename = "Rent_Research_Facility")]
+    RentResearchFacility,
+    #[serde(rename = "Security_Officer")]
+    SecurityOfficer,
+    #[serde(rename = "Skill_Plan_Manager")]
+    SkillPlanManager,
+    #[serde(rename = "Starbase_Defense_Operator")]
+    StarbaseDefenseOperator,
+    #[serde(rename = "Starbase_Fuel_Technician")]
+    StarbaseFuelTechnician,
+    #[serde(rename = "Station_Manager")]
+    StationManager,
+    #[serde(rename = "Trader")]
+    Trader,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+/// Check a list of roles for a role, treating directors as having every role.
+pub(crate) fn roles_include(roles: Option<&[CorpRole]>, role: &CorpRole) -> bool {
+    roles.is_some_and(|roles| {
+        roles
+            .iter()
+            .any(|held| held == role || *held == CorpRole::Director)
+    })
+}
+
+/// The roles of a corporation member.
+///
+/// Roles are held either globally or only at the corporation's base,
+/// headquarters, or other offices; the grantable roles are those the
+/// member can grant to others.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct MemberRoles {
+    pub character_id: i32,
+    pub grantable_roles: Option<Vec<CorpRole>>,
+    pub grantable_roles_at_base: Option<Vec<CorpRole>>,
+    pub grantable_roles_at_hq: Option<Vec<CorpRole>>,
+    pub grantable_roles_at_other: Option<Vec<CorpRole>>,
+    pub roles: Option<Vec<CorpRole>>,
+    pub roles_at_base: Option<Vec<CorpRole>>,
+    pub roles_at_hq: Option<Vec<CorpRole>>,
+    pub roles_at_other: Option<Vec<CorpRole>>,
+}
+
+impl MemberRoles {
+    /// Whether the member holds a role globally.
+    ///
+    /// Directors are treated as holding every role.
+    pub fn has_role(&self, role: &CorpRole) -> bool {
+        roles_include(self.roles.as_deref(), role)
+    }
+}
+
 /// Endpoints for Corporation
 pub struct CorporationGroup<'a> {
     pub(crate) esi: &'a Esi,
@@ -55,11 +386,45 @@ impl CorporationGroup<'_> {
     api_get!(
         /// Get a corporation's member list.
         ///
+        /// ESI returns every member in one response, without pagination,
+        /// however large the corporation.
+        ///
         /// Requires the auth'd character to be in the corporation.
         get_members,
         "get_corporations_corporation_id_members",
         RequestType::Authenticated,
-        Vec<u64>,
+        Vec<i64>,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
+    /// Get a corporation's member list, with each member's name.
+    ///
+    /// Requires the auth'd character to be in the corporation.
+    pub async fn get_members_named(
+        &self,
+        corporation_id: i32,
+    ) -> EsiResult<Vec<CorporationMemberName>> {
+        let members = self.get_members(corporation_id).await?;
+        let mut names = self.esi.resolve_names(&members).await?;
+        Ok(members
+            .into_iter()
+            .filter_map(|id| {
+                names.remove(&id).map(|resolved| CorporationMemberName {
+                    character_id: id,
+                    name: resolved.name,
+                })
+            })
+            .collect())
+    }
+
+    api_get!(
+        /// Get the names of a corporation's hangar and wallet divisions.
+        ///
+        /// Requires the auth'd character to be a director of the corp.
+        get_divisions,
+        "get_corporations_corporation_id_divisions",
+        RequestType::Authenticated,
+        CorporationDivisions,
         (corporation_id: i32) => "{corporation_id}"
     );
 
@@ -71,5 +436,190 @@ impl CorporationGroup<'_> {
         Vec<u64>,
     );
 
+    api_get!(
+        /// Get a page of the structures a corporation owns.
+        ///
+        /// Requires the auth'd character to have the station manager role in the corp.
+        get_structures,
+        "get_corporations_corporation_id_structures",
+        RequestType::Authenticated,
+        Vec<CorpStructure>,
+        (corporation_id: i32) => "{corporation_id}";
+        Optional(page: i32) => "page"
+    );
+
+    api_get_paged!(
+        /// Get a page of the structures a corporation owns, with the total page count.
+        ///
+        /// Requires the auth'd character to have the station manager role in the corp.
+        get_structures_paged,
+        "get_corporations_corporation_id_structures",
+        RequestType::Authenticated,
+        CorpStructure,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
+    api_get!(
+        /// Get the roles of every member of a corporation.
+        ///
+        /// Requires the auth'd character to be a director of the corp.
+        get_member_roles,
+        "get_corporations_corporation_id_roles",
+        RequestType::Authenticated,
+        Vec<MemberRoles>,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
+    api_get!(
+        /// Get the last logon and logoff, location, and ship of
+        /// every member of a corporation.
+        ///
+        /// Requires the auth'd character to be a director of the corp.
+        get_member_tracking,
+        "get_corporations_corporation_id_membertracking",
+        RequestType::Authenticated,
+        Vec<MemberTracking>,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
     // more endpoints ...
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CorpRole, CorpStructure, CorpStructureState, MemberRoles, MemberTracking};
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_corp_structure() {
+        let source = r#"{
+            "corporation_id": 98000001,
+            "fuel_expires": "2024-03-10T12:00:00Z",
+            "profile_id": 1,
+            "services": [{ "name": "Market", "state": "online" }],
+            "state": "armor_reinforce",
+            "state_timer_end": "2024-03-02T18:00:00Z",
+            "structure_id": 1021000000000,
+            "system_id": 30000142,
+            "type_id": 35832
+        }"#;
+        let structure: CorpStructure = serde_json::from_str(source).unwrap();
+        assert!(structure.state.is_reinforced());
+        assert!(!structure.state.is_vulnerable());
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 8, 12, 0, 0).unwrap();
+        assert_eq!(
+            structure.fuel_expires_in(now).unwrap(),
+            Some(Duration::days(2))
+        );
+        assert_eq!(
+            structure.state_timer_end().unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 3, 2, 18, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_corp_structure_state_fallback() {
+        let states: Vec<CorpStructureState> =
+            serde_json::from_str(r#"["shield_vulnerable", "unknown", "something_new"]"#).unwrap();
+        assert_eq!(
+            states,
+            vec![
+                CorpStructureState::ShieldVulnerable,
+                CorpStructureState::Unknown,
+                CorpStructureState::Other("something_new".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_member_roles() {
+        let source = r#"{
+            "character_id": 2112000000,
+            "grantable_roles": ["Hangar_Take_1"],
+            "roles": ["Station_Manager", "Account_Take_3", "Some_New_Role"],
+            "roles_at_hq": ["Factory_Manager"]
+        }"#;
+        let member: MemberRoles = serde_json::from_str(source).unwrap();
+        assert_eq!(
+            member.roles.as_deref(),
+            Some(
+                &[
+                    CorpRole::StationManager,
+                    CorpRole::AccountTake3,
+                    CorpRole::Unknown("Some_New_Role".to_owned()),
+                ][..]
+            )
+        );
+        assert!(member.has_role(&CorpRole::StationManager));
+        assert!(!member.has_role(&CorpRole::FactoryManager));
+        assert_eq!(
+            serde_json::to_string(&CorpRole::HangarTake1).unwrap(),
+            r#""Hangar_Take_1""#
+        );
+
+        let director: MemberRoles =
+            serde_json::from_str(r#"{ "character_id": 1, "roles": ["Director"] }"#).unwrap();
+        assert!(director.has_role(&CorpRole::Accountant));
+    }
+
+    #[test]
+    fn test_member_tracking_activity() {
+        let source = r#"[
+            {
+              "base_id": 60003760,
+              "character_id": 2112000001,
+              "location_id": 30000142,
+              "logoff_date": "2024-01-01T02:00:00Z",
+              "logon_date": "2024-01-01T00:00:00Z",
+              "ship_type_id": 670,
+              "start_date": "2023-06-01T00:00:00Z"
+            },
+            {
+              "character_id": 2112000002,
+              "start_date": "2023-12-01T00:00:00Z"
+            }
+          ]"#;
+        let members: Vec<MemberTracking> = serde_json::from_str(source).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+
+        assert_eq!(members[0].days_since_logon(now).unwrap(), Some(30));
+        assert!(members[0].likely_inactive(30, now).unwrap());
+        assert!(!members[0].likely_inactive(31, now).unwrap());
+        assert_eq!(members[1].days_since_logon(now).unwrap(), None);
+        assert!(members[1].likely_inactive(365, now).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_members() {
+        use crate::prelude::*;
+        use crate::test_server::{serve, Response};
+
+        let base_url = serve(|request| {
+            assert_eq!(request.method, "GET");
+            // "static_paths" swaps the version for "latest"
+            assert!(request.target.contains("/corporations/98000001/members/"));
+            assert_eq!(request.header("authorization"), Some("Bearer abc"));
+            Response::json("[2112000001, 2147483700]")
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(serde_json::json!({
+                "paths": {
+                    "/v4/corporations/{corporation_id}/members/": {
+                        "get": { "operationId": "get_corporations_corporation_id_members" }
+                    },
+                }
+            })))
+            .access_token(Some("abc"))
+            .access_expiration(Some(60_000))
+            .clock(|| 0)
+            .build()
+            .unwrap();
+
+        let members = esi.group_corporation().get_members(98000001).await.unwrap();
+        assert_eq!(members, vec![2112000001, 2147483700]);
+    }
+}
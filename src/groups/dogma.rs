@@ -1,8 +1,187 @@
-#![allow(unused)]
-
 use crate::prelude::*;
 
 /// Endpoints for Dogma
 pub struct DogmaGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct DogmaAttribute {
+    pub attribute_id: i32,
+    pub default_value: Option<f32>,
+    pub description: Option<String>,
+    pub display_name: Option<String>,
+    pub high_is_good: Option<bool>,
+    pub icon_id: Option<i32>,
+    pub name: Option<String>,
+    pub published: Option<bool>,
+    pub stackable: Option<bool>,
+    pub unit_id: Option<i32>,
+}
+
+/// One modification that a dogma effect makes when it is applied.
+///
+/// `func` says what the modifier targets, such as `ItemModifier` for the
+/// item itself or `LocationGroupModifier` for items of a group in the
+/// same location, and `domain` says where to look for that target.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct DogmaEffectModifier {
+    pub domain: Option<String>,
+    pub effect_id: Option<i32>,
+    pub func: String,
+    pub modified_attribute_id: Option<i32>,
+    pub modifying_attribute_id: Option<i32>,
+    pub operator: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct DogmaEffect {
+    pub description: Option<String>,
+    pub disallow_auto_repeat: Option<bool>,
+    pub discharge_attribute_id: Option<i32>,
+    pub display_name: Option<String>,
+    pub duration_attribute_id: Option<i32>,
+    pub effect_category: Option<i32>,
+    pub effect_id: i32,
+    pub electronic_chance: Option<bool>,
+    pub falloff_attribute_id: Option<i32>,
+    pub icon_id: Option<i32>,
+    pub is_assistance: Option<bool>,
+    pub is_offensive: Option<bool>,
+    pub is_warp_safe: Option<bool>,
+    pub modifiers: Option<Vec<DogmaEffectModifier>>,
+    pub name: Option<String>,
+    pub post_expression: Option<i32>,
+    pub pre_expression: Option<i32>,
+    pub published: Option<bool>,
+    pub range_attribute_id: Option<i32>,
+    pub range_chance: Option<bool>,
+    pub tracking_speed_attribute_id: Option<i32>,
+}
+
+impl DogmaEffect {
+    /// Get the modifiers of the effect, which is empty if it has none.
+    pub fn modifiers(&self) -> &[DogmaEffectModifier] {
+        self.modifiers.as_deref().unwrap_or_default()
+    }
+
+    /// Get each pair of `(modifying, modified)` attribute IDs from the
+    /// effect's modifiers: the value of the modifying attribute on the
+    /// source item is applied to the modified attribute on the target.
+    ///
+    /// Modifiers without both attributes are skipped.
+    pub fn attribute_modifications(&self) -> Vec<(i32, i32)> {
+        self.modifiers()
+            .iter()
+            .filter_map(|m| Some((m.modifying_attribute_id?, m.modified_attribute_id?)))
+            .collect()
+    }
+
+    /// Get the IDs of the attributes that the effect modifies.
+    pub fn modified_attribute_ids(&self) -> Vec<i32> {
+        let mut ids: Vec<i32> = self
+            .modifiers()
+            .iter()
+            .filter_map(|m| m.modified_attribute_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Get the IDs of the attributes whose values the effect applies.
+    pub fn modifying_attribute_ids(&self) -> Vec<i32> {
+        let mut ids: Vec<i32> = self
+            .modifiers()
+            .iter()
+            .filter_map(|m| m.modifying_attribute_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+impl DogmaGroup<'_> {
+    api_get!(
+        /// Get the IDs of every dogma attribute.
+        get_attributes,
+        "get_dogma_attributes",
+        RequestType::Public,
+        Vec<i32>,
+    );
+
+    api_get!(
+        /// Get information about a dogma attribute.
+        get_attribute,
+        "get_dogma_attributes_attribute_id",
+        RequestType::Public,
+        DogmaAttribute,
+        (attribute_id: i32) => "{attribute_id}"
+    );
+
+    api_get!(
+        /// Get the IDs of every dogma effect.
+        get_effects,
+        "get_dogma_effects",
+        RequestType::Public,
+        Vec<i32>,
+    );
+
+    api_get!(
+        /// Get information about a dogma effect, including its modifiers.
+        get_effect,
+        "get_dogma_effects_effect_id",
+        RequestType::Public,
+        DogmaEffect,
+        (effect_id: i32) => "{effect_id}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DogmaEffect;
+
+    #[test]
+    fn test_effect_modifiers() {
+        let source = r#"{
+            "description": "",
+            "display_name": "",
+            "effect_category": 0,
+            "effect_id": 1230,
+            "modifiers": [
+                {
+                    "domain": "shipID",
+                    "func": "LocationRequiredSkillModifier",
+                    "modified_attribute_id": 54,
+                    "modifying_attribute_id": 292,
+                    "operator": 6
+                },
+                {
+                    "domain": "shipID",
+                    "func": "LocationRequiredSkillModifier",
+                    "modified_attribute_id": 158,
+                    "modifying_attribute_id": 292,
+                    "operator": 6
+                },
+                { "effect_id": 16, "func": "EffectStopper" }
+            ],
+            "name": "weaponUpgradesCpuNeedBonusPostPercentCpuLocationShipModulesRequiringGunnery",
+            "published": false
+        }"#;
+        let effect: DogmaEffect = serde_json::from_str(source).unwrap();
+        assert_eq!(effect.modifiers().len(), 3);
+        assert_eq!(
+            effect.attribute_modifications(),
+            vec![(292, 54), (292, 158)]
+        );
+        assert_eq!(effect.modified_attribute_ids(), vec![54, 158]);
+        assert_eq!(effect.modifying_attribute_ids(), vec![292]);
+    }
+}
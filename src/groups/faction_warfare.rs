@@ -5,14 +5,16 @@ pub struct FactionWarfareGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FactionLeaderboardItem {
     pub amount: Option<i32>,
     pub faction_id: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FactionLeaderboardListing {
     pub active_total: Vec<FactionLeaderboardItem>,
@@ -20,21 +22,24 @@ pub struct FactionLeaderboardListing {
     pub yesterday: Vec<FactionLeaderboardItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWLeaderboards {
     pub kills: FactionLeaderboardListing,
     pub victory_points: FactionLeaderboardListing,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterLeaderboardItem {
     pub amount: i32,
     pub character_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterLeaderboardListing {
     pub active_total: Vec<CharacterLeaderboardItem>,
@@ -42,21 +47,24 @@ pub struct CharacterLeaderboardListing {
     pub yesterday: Vec<CharacterLeaderboardItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWCharacterLeaderboards {
     pub kills: CharacterLeaderboardListing,
     pub victory_points: CharacterLeaderboardListing,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CorporationLeaderboardItem {
     pub amount: i32,
     pub corporation_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CorporationLeaderboardListing {
     pub active_total: Vec<CorporationLeaderboardItem>,
@@ -64,14 +72,16 @@ pub struct CorporationLeaderboardListing {
     pub yesterday: Vec<CorporationLeaderboardItem>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWCorporationLeaderboards {
     pub kills: CorporationLeaderboardListing,
     pub victory_points: CorporationLeaderboardListing,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWStatsItemRange {
     pub total: i32,
@@ -79,34 +89,69 @@ pub struct FWStatsItemRange {
     pub yesterday: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWStatsItem {
-    pub faction_id: u32,
+    pub faction_id: i32,
     pub kills: FWStatsItemRange,
     pub pilots: u32,
     pub systems_controlled: u32,
     pub victory_points: FWStatsItemRange,
 }
 
-#[derive(Debug, Deserialize)]
+/// How contested a faction warfare system is.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[allow(missing_docs)]
+pub enum FWContestState {
+    Captured,
+    Contested,
+    Uncontested,
+    Vulnerable,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWSystem {
-    pub contested: String,
-    pub occupier_faction_id: u8,
-    pub owner_faction_id: u32,
-    pub solar_system_id: u32,
+    pub contested: FWContestState,
+    pub occupier_faction_id: i32,
+    pub owner_faction_id: i32,
+    pub solar_system_id: i32,
     pub victory_points: u32,
     pub victory_points_threshold: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct FWWar {
     pub faction_id: i32,
     pub against_id: i32,
 }
 
+/// A faction warfare system along with the names of the system
+/// and its factions.
+///
+/// A name is `None` if ESI did not resolve it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamedFWSystem {
+    /// The system.
+    pub system: FWSystem,
+    /// Name of the solar system.
+    pub solar_system_name: Option<String>,
+    /// Name of the faction occupying the system.
+    pub occupier_faction_name: Option<String>,
+    /// Name of the faction that owns the system.
+    pub owner_faction_name: Option<String>,
+}
+
 impl FactionWarfareGroup<'_> {
     api_get!(
         /// Get the top 4 leaderboards of factions for total, last week, and yesterday.
@@ -148,6 +193,36 @@ impl FactionWarfareGroup<'_> {
         Vec<FWSystem>,
     );
 
+    /// Get FW system ownership, with the names of the systems
+    /// and factions.
+    ///
+    /// The names are looked up with [`Esi::resolve_names`].
+    pub async fn systems_named(&self) -> EsiResult<Vec<NamedFWSystem>> {
+        let systems = self.systems().await?;
+        let ids: Vec<i64> = systems
+            .iter()
+            .flat_map(|system| {
+                [
+                    system.solar_system_id,
+                    system.occupier_faction_id,
+                    system.owner_faction_id,
+                ]
+            })
+            .map(i64::from)
+            .collect();
+        let names = self.esi.resolve_names(&ids).await?;
+        let name_of = |id: i32| names.get(&i64::from(id)).map(|n| n.name.clone());
+        Ok(systems
+            .into_iter()
+            .map(|system| NamedFWSystem {
+                solar_system_name: name_of(system.solar_system_id),
+                occupier_faction_name: name_of(system.occupier_faction_id),
+                owner_faction_name: name_of(system.owner_faction_id),
+                system,
+            })
+            .collect())
+    }
+
     api_get!(
         /// Get FW faction information.
         wars,
@@ -158,3 +233,38 @@ impl FactionWarfareGroup<'_> {
 
     // more endpoints ...
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{FWContestState, FWSystem};
+
+    #[test]
+    fn test_fw_system_deserialize() {
+        let source = r#"[
+            {
+              "contested": "vulnerable",
+              "occupier_faction_id": 500001,
+              "owner_faction_id": 500001,
+              "solar_system_id": 30002057,
+              "victory_points": 60000,
+              "victory_points_threshold": 75000
+            },
+            {
+              "contested": "frontline",
+              "occupier_faction_id": 500004,
+              "owner_faction_id": 500004,
+              "solar_system_id": 30002058,
+              "victory_points": 0,
+              "victory_points_threshold": 75000
+            }
+          ]"#;
+        let systems: Vec<FWSystem> = serde_json::from_str(source).unwrap();
+
+        assert_eq!(systems[0].contested, FWContestState::Vulnerable);
+        assert_eq!(systems[0].occupier_faction_id, 500001);
+        assert_eq!(
+            systems[1].contested,
+            FWContestState::Unknown("frontline".to_owned())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-#![allow(unused)]
+use log::warn;
 
 use crate::prelude::*;
 
@@ -6,3 +6,135 @@ use crate::prelude::*;
 pub struct FittingsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FittingItem {
+    pub flag: String,
+    pub quantity: i32,
+    pub type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct Fitting {
+    pub description: String,
+    pub fitting_id: i32,
+    pub items: Vec<FittingItem>,
+    pub name: String,
+    pub ship_type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct NewFitting {
+    pub description: String,
+    pub items: Vec<FittingItem>,
+    pub name: String,
+    pub ship_type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CreatedFitting {
+    pub fitting_id: i32,
+}
+
+impl FittingsGroup<'_> {
+    api_get!(
+        /// Get a character's saved fittings.
+        get_fittings,
+        "get_characters_character_id_fittings",
+        RequestType::Authenticated,
+        Vec<Fitting>,
+        (character_id: i32) => "{character_id}"
+    );
+
+    /// Save a new fitting for a character.
+    ///
+    /// Each item is checked before the request is made: a quantity
+    /// below 1 is an error, while a slot flag this crate doesn't know
+    /// about is only logged as a warning and sent to ESI as-is.
+    pub async fn create_fitting(
+        &self,
+        character_id: i32,
+        fitting: &NewFitting,
+    ) -> EsiResult<CreatedFitting> {
+        validate_fitting_items(&fitting.items)?;
+        let path = self
+            .esi
+            .get_wrapped_endpoint("post_characters_character_id_fittings")?
+            .replace("{character_id}", &character_id.to_string());
+        let body = serde_json::to_string(fitting)?;
+        self.esi
+            .query("POST", RequestType::Authenticated, &path, None, Some(&body))
+            .await
+    }
+}
+
+/// Check whether the flag is one of the slot flags that ESI accepts for fittings.
+fn is_known_flag(flag: &str) -> bool {
+    let indexed = |prefix: &str, max: u8| {
+        flag.strip_prefix(prefix)
+            .and_then(|n| n.parse::<u8>().ok())
+            .is_some_and(|n| n <= max)
+    };
+    matches!(flag, "Cargo" | "DroneBay" | "FighterBay" | "Invalid")
+        || indexed("HiSlot", 7)
+        || indexed("MedSlot", 7)
+        || indexed("LoSlot", 7)
+        || indexed("RigSlot", 2)
+        || indexed("ServiceSlot", 7)
+        || indexed("SubSystemSlot", 3)
+}
+
+/// Validate fitting items before sending them to ESI.
+fn validate_fitting_items(items: &[FittingItem]) -> EsiResult<()> {
+    for item in items {
+        if item.quantity < 1 {
+            return Err(EsiError::InvalidFittingItem(format!(
+                "type {} in '{}' has quantity {}",
+                item.type_id, item.flag, item.quantity
+            )));
+        }
+        if !is_known_flag(&item.flag) {
+            warn!(
+                "Unknown fitting flag '{}' for type {}",
+                item.flag, item.type_id
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_known_flag, validate_fitting_items, FittingItem};
+
+    #[test]
+    fn test_is_known_flag() {
+        assert!(is_known_flag("Cargo"));
+        assert!(is_known_flag("HiSlot0"));
+        assert!(is_known_flag("RigSlot2"));
+        assert!(is_known_flag("SubSystemSlot3"));
+        assert!(!is_known_flag("RigSlot3"));
+        assert!(!is_known_flag("HiSlot"));
+        assert!(!is_known_flag("hislot0"));
+    }
+
+    #[test]
+    fn test_validate_fitting_items() {
+        let item = |flag: &str, quantity| FittingItem {
+            flag: flag.to_owned(),
+            quantity,
+            type_id: 1,
+        };
+        assert!(validate_fitting_items(&[item("HiSlot0", 1), item("Cargo", 100)]).is_ok());
+        assert!(validate_fitting_items(&[item("SomeNewBay", 1)]).is_ok());
+        assert!(validate_fitting_items(&[item("LoSlot0", 0)]).is_err());
+    }
+}
@@ -6,3 +6,207 @@ use crate::prelude::*;
 pub struct FleetsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FleetSquad {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FleetWing {
+    pub id: i64,
+    pub name: String,
+    pub squads: Vec<FleetSquad>,
+}
+
+/// New name for a wing or squad.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FleetNaming {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CreatedWing {
+    pub wing_id: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CreatedSquad {
+    pub squad_id: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FleetMember {
+    pub character_id: i32,
+    pub join_time: String,
+    pub role: String,
+    pub role_name: String,
+    pub ship_type_id: i32,
+    pub solar_system_id: i32,
+    pub squad_id: i64,
+    pub station_id: Option<i64>,
+    pub takes_fleet_warp: bool,
+    pub wing_id: i64,
+}
+
+/// A fleet member along with the names of their character, ship type,
+/// and solar system.
+///
+/// A name is `None` if ESI did not resolve it.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamedFleetMember {
+    /// The fleet member.
+    pub member: FleetMember,
+    /// Name of the member's character.
+    pub character_name: Option<String>,
+    /// Name of the member's ship type.
+    pub ship_type_name: Option<String>,
+    /// Name of the member's solar system.
+    pub solar_system_name: Option<String>,
+}
+
+impl FleetsGroup<'_> {
+    api_get!(
+        /// Get the members of a fleet.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        get_members,
+        "get_fleets_fleet_id_members",
+        RequestType::Authenticated,
+        Vec<FleetMember>,
+        (fleet_id: i64) => "{fleet_id}"
+    );
+
+    /// Get the members of a fleet, with the names of their characters,
+    /// ship types, and solar systems.
+    ///
+    /// The names are resolved together through `post_universe_names`.
+    /// Requires the auth'd character to be the fleet boss.
+    pub async fn get_members_named(&self, fleet_id: i64) -> EsiResult<Vec<NamedFleetMember>> {
+        let members = self.get_members(fleet_id).await?;
+        let ids: Vec<i64> = members
+            .iter()
+            .flat_map(|member| {
+                [
+                    member.character_id,
+                    member.ship_type_id,
+                    member.solar_system_id,
+                ]
+            })
+            .map(i64::from)
+            .collect();
+        let names = self.esi.resolve_names(&ids).await?;
+        let name_of = |id: i32| names.get(&i64::from(id)).map(|n| n.name.clone());
+        Ok(members
+            .into_iter()
+            .map(|member| NamedFleetMember {
+                character_name: name_of(member.character_id),
+                ship_type_name: name_of(member.ship_type_id),
+                solar_system_name: name_of(member.solar_system_id),
+                member,
+            })
+            .collect())
+    }
+
+    api_get!(
+        /// Get a fleet's wings and their squads.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        get_wings,
+        "get_fleets_fleet_id_wings",
+        RequestType::Authenticated,
+        Vec<FleetWing>,
+        (fleet_id: i64) => "{fleet_id}"
+    );
+
+    /// Create a new wing in a fleet.
+    ///
+    /// Requires the auth'd character to be the fleet boss.
+    pub async fn create_wing(&self, fleet_id: i64) -> EsiResult<CreatedWing> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("post_fleets_fleet_id_wings")?
+            .replace("{fleet_id}", &fleet_id.to_string());
+        self.esi
+            .query("POST", RequestType::Authenticated, &path, None, None)
+            .await
+    }
+
+    api_delete!(
+        /// Delete a wing from a fleet. The wing must be empty.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        delete_wing,
+        "delete_fleets_fleet_id_wings_wing_id",
+        RequestType::Authenticated,
+        (),
+        (fleet_id: i64) => "{fleet_id}",
+        (wing_id: i64) => "{wing_id}"
+    );
+
+    api_put!(
+        /// Rename a wing in a fleet.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        rename_wing,
+        "put_fleets_fleet_id_wings_wing_id",
+        RequestType::Authenticated,
+        (),
+        (fleet_id: i64) => "{fleet_id}",
+        (wing_id: i64) => "{wing_id}",
+        naming: &FleetNaming,
+    );
+
+    /// Create a new squad in one of a fleet's wings.
+    ///
+    /// Requires the auth'd character to be the fleet boss.
+    pub async fn create_squad(&self, fleet_id: i64, wing_id: i64) -> EsiResult<CreatedSquad> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("post_fleets_fleet_id_wings_wing_id_squads")?
+            .replace("{fleet_id}", &fleet_id.to_string())
+            .replace("{wing_id}", &wing_id.to_string());
+        self.esi
+            .query("POST", RequestType::Authenticated, &path, None, None)
+            .await
+    }
+
+    api_delete!(
+        /// Delete a squad from a fleet. The squad must be empty.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        delete_squad,
+        "delete_fleets_fleet_id_squads_squad_id",
+        RequestType::Authenticated,
+        (),
+        (fleet_id: i64) => "{fleet_id}",
+        (squad_id: i64) => "{squad_id}"
+    );
+
+    api_put!(
+        /// Rename a squad in a fleet.
+        ///
+        /// Requires the auth'd character to be the fleet boss.
+        rename_squad,
+        "put_fleets_fleet_id_squads_squad_id",
+        RequestType::Authenticated,
+        (),
+        (fleet_id: i64) => "{fleet_id}",
+        (squad_id: i64) => "{squad_id}",
+        naming: &FleetNaming,
+    );
+}
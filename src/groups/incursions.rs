@@ -5,13 +5,14 @@ pub struct IncursionsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Incursion {
     pub constellation_id: i32,
     pub faction_id: i32,
     pub has_boss: bool,
-    pub infested_solar_systems: Vec<u32>,
+    pub infested_solar_systems: Vec<i32>,
     pub influence: f64,
     pub staging_solar_system_id: i32,
     pub state: String,
@@ -19,6 +20,26 @@ pub struct Incursion {
     pub incursion_type: String,
 }
 
+/// An incursion along with the names of its constellation, faction,
+/// and systems.
+///
+/// A name is `None` if ESI did not resolve it.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamedIncursion {
+    /// The incursion.
+    pub incursion: Incursion,
+    /// Name of the incursion's constellation.
+    pub constellation_name: Option<String>,
+    /// Name of the incursion's faction.
+    pub faction_name: Option<String>,
+    /// Name of the incursion's staging system.
+    pub staging_solar_system_name: Option<String>,
+    /// Names of the infested systems, in the same order as
+    /// `incursion.infested_solar_systems`.
+    pub infested_solar_system_names: Vec<Option<String>>,
+}
+
 impl IncursionsGroup<'_> {
     api_get!(
         /// Get the current incursions.
@@ -27,4 +48,41 @@ impl IncursionsGroup<'_> {
         RequestType::Public,
         Vec<Incursion>,
     );
+
+    /// Get the current incursions, with the names of their
+    /// constellations, factions, and systems.
+    ///
+    /// The names are looked up with [`Esi::resolve_names`].
+    pub async fn list_named(&self) -> EsiResult<Vec<NamedIncursion>> {
+        let incursions = self.list().await?;
+        let ids: Vec<i64> = incursions
+            .iter()
+            .flat_map(|incursion| {
+                [
+                    incursion.constellation_id,
+                    incursion.faction_id,
+                    incursion.staging_solar_system_id,
+                ]
+                .into_iter()
+                .chain(incursion.infested_solar_systems.iter().copied())
+            })
+            .map(i64::from)
+            .collect();
+        let names = self.esi.resolve_names(&ids).await?;
+        let name_of = |id: i32| names.get(&i64::from(id)).map(|n| n.name.clone());
+        Ok(incursions
+            .into_iter()
+            .map(|incursion| NamedIncursion {
+                constellation_name: name_of(incursion.constellation_id),
+                faction_name: name_of(incursion.faction_id),
+                staging_solar_system_name: name_of(incursion.staging_solar_system_id),
+                infested_solar_system_names: incursion
+                    .infested_solar_systems
+                    .iter()
+                    .map(|&system| name_of(system))
+                    .collect(),
+                incursion,
+            })
+            .collect())
+    }
 }
@@ -1,5 +1,8 @@
 #![allow(unused)]
 
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
 use crate::prelude::*;
 
 /// Endpoints for Industry
@@ -7,21 +10,24 @@ pub struct IndustryGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CostIndex {
     pub activity: String,
     pub cost_index: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct IndustrialSystem {
     pub cost_indices: Vec<CostIndex>,
     pub solar_system_id: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct IndustryJob {
     pub activity_id: i32,
@@ -48,6 +54,16 @@ pub struct IndustryJob {
     pub successful_runs: Option<i32>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct MiningLedgerEntry {
+    pub date: String,
+    pub quantity: i64,
+    pub solar_system_id: i32,
+    pub type_id: i32,
+}
+
 impl IndustryGroup<'_> {
     api_get!(
         /// Returns a list of solar systems with the cost index for every
@@ -67,4 +83,139 @@ impl IndustryGroup<'_> {
         (character_id: i32) => "{character_id}";
         Optional(include_completed: bool) => "include_completed"
     );
+
+    api_get!(
+        /// Get a page of a character's mining ledger, covering the last 30 days.
+        get_character_mining,
+        "get_characters_character_id_mining",
+        RequestType::Authenticated,
+        Vec<MiningLedgerEntry>,
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
+    );
+
+    api_get_paged!(
+        /// Get a page of a character's mining ledger, with the total page count.
+        get_character_mining_paged,
+        "get_characters_character_id_mining",
+        RequestType::Authenticated,
+        MiningLedgerEntry,
+        (character_id: i32) => "{character_id}"
+    );
+
+    /// Get a character's whole mining ledger, fetching every page.
+    pub async fn get_all_character_mining(
+        &self,
+        character_id: i32,
+    ) -> EsiResult<Vec<MiningLedgerEntry>> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_characters_character_id_mining")?
+            .replace("{character_id}", &character_id.to_string());
+        self.esi
+            .query_all_pages("GET", RequestType::Authenticated, &path, None)
+            .await
+    }
+
+    /// Get the total quantity a character mined of each type, between
+    /// two dates (inclusive).
+    ///
+    /// Every page of the mining ledger is fetched. The result maps type ID
+    /// to quantity.
+    pub async fn get_character_mining_totals(
+        &self,
+        character_id: i32,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> EsiResult<HashMap<i32, i64>> {
+        let entries = self.get_all_character_mining(character_id).await?;
+        sum_mining_by(&entries, from, to, |entry| entry.type_id)
+    }
+
+    /// Get the total quantity a character mined in each solar system,
+    /// between two dates (inclusive).
+    ///
+    /// Every page of the mining ledger is fetched. The result maps solar
+    /// system ID to quantity, across all types.
+    pub async fn get_character_mining_totals_by_system(
+        &self,
+        character_id: i32,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> EsiResult<HashMap<i32, i64>> {
+        let entries = self.get_all_character_mining(character_id).await?;
+        sum_mining_by(&entries, from, to, |entry| entry.solar_system_id)
+    }
+}
+
+/// Sum mining ledger quantities by a key, such as the type or solar
+/// system, for entries between two dates (inclusive).
+fn sum_mining_by(
+    entries: &[MiningLedgerEntry],
+    from: NaiveDate,
+    to: NaiveDate,
+    key: impl Fn(&MiningLedgerEntry) -> i32,
+) -> EsiResult<HashMap<i32, i64>> {
+    let mut totals = HashMap::new();
+    for entry in entries {
+        let date = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d")?;
+        if (from..=to).contains(&date) {
+            *totals.entry(key(entry)).or_insert(0) += entry.quantity;
+        }
+    }
+    Ok(totals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sum_mining_by, MiningLedgerEntry};
+    use chrono::NaiveDate;
+
+    fn entry(date: &str, type_id: i32, quantity: i64) -> MiningLedgerEntry {
+        MiningLedgerEntry {
+            date: date.to_owned(),
+            quantity,
+            solar_system_id: 30000142,
+            type_id,
+        }
+    }
+
+    #[test]
+    fn test_sum_mining_by_type() {
+        let entries = [
+            entry("2024-03-01", 1230, 100),
+            entry("2024-03-02", 1230, 250),
+            entry("2024-03-02", 1228, 40),
+            entry("2024-03-05", 1230, 1000),
+        ];
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let totals = sum_mining_by(&entries, day(1), day(4), |e| e.type_id).unwrap();
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&1230], 350);
+        assert_eq!(totals[&1228], 40);
+        assert!(
+            sum_mining_by(&[entry("03/01/2024", 1, 1)], day(1), day(4), |e| e.type_id).is_err()
+        );
+    }
+
+    #[test]
+    fn test_sum_mining_by_system() {
+        let in_system = |system, date, quantity| MiningLedgerEntry {
+            solar_system_id: system,
+            ..entry(date, 1230, quantity)
+        };
+        let entries = [
+            in_system(30000142, "2024-03-01", 100),
+            in_system(30000144, "2024-03-02", 250),
+            in_system(30000142, "2024-03-03", 40),
+            in_system(30000144, "2024-02-28", 1000),
+        ];
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let totals = sum_mining_by(&entries, day(1), day(4), |e| e.solar_system_id).unwrap();
+
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[&30000142], 140);
+        assert_eq!(totals[&30000144], 250);
+    }
 }
@@ -1,8 +1,137 @@
-#![allow(unused)]
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use crate::prelude::*;
 
+/// How long to keep insurance prices before fetching them again.
+const INSURANCE_PRICES_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Shared cache of the insurance price list, and when it was fetched
+/// in milliseconds since the Unix epoch.
+pub(crate) type InsurancePriceCache = Arc<Mutex<Option<(i64, Arc<Vec<InsurancePrice>>)>>>;
+
 /// Endpoints for Insurance
 pub struct InsuranceGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct InsuranceLevel {
+    pub cost: f64,
+    pub name: String,
+    pub payout: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct InsurancePrice {
+    pub levels: Vec<InsuranceLevel>,
+    pub type_id: i32,
+}
+
+impl InsurancePrice {
+    /// Get the payout of an insurance level, such as "Platinum".
+    ///
+    /// The level name is matched case-insensitively.
+    pub fn payout_for(&self, level_name: &str) -> Option<f64> {
+        self.levels
+            .iter()
+            .find(|level| level.name.eq_ignore_ascii_case(level_name))
+            .map(|level| level.payout)
+    }
+
+    /// Get the highest payout of any insurance level.
+    pub fn best_payout(&self) -> Option<f64> {
+        self.levels
+            .iter()
+            .map(|level| level.payout)
+            .reduce(f64::max)
+    }
+}
+
+impl InsuranceGroup<'_> {
+    api_get!(
+        /// Get the insurance levels for every ship type.
+        get_insurance_prices,
+        "get_insurance_prices",
+        RequestType::Public,
+        Vec<InsurancePrice>,
+    );
+
+    /// Get the payout of an insurance level, such as "Platinum", for a
+    /// ship type.
+    ///
+    /// Returns `None` if the ship can't be insured or has no such level.
+    /// The full price list is kept on the `Esi` struct for an hour.
+    pub async fn payout_for(&self, type_id: i32, level_name: &str) -> EsiResult<Option<f64>> {
+        Ok(self
+            .price_for(type_id)
+            .await?
+            .and_then(|price| price.payout_for(level_name)))
+    }
+
+    /// Get the highest insurance payout for a ship type.
+    ///
+    /// Returns `None` if the ship can't be insured. The full price list
+    /// is kept on the `Esi` struct for an hour.
+    pub async fn best_payout(&self, type_id: i32) -> EsiResult<Option<f64>> {
+        Ok(self
+            .price_for(type_id)
+            .await?
+            .and_then(|price| price.best_payout()))
+    }
+
+    /// Get the insurance levels for a ship type, using the cached
+    /// price list where possible.
+    async fn price_for(&self, type_id: i32) -> EsiResult<Option<InsurancePrice>> {
+        let prices = self.cached_prices().await?;
+        Ok(prices
+            .iter()
+            .find(|price| price.type_id == type_id)
+            .cloned())
+    }
+
+    /// Get the insurance price list, fetching it if the cached copy is
+    /// missing or stale.
+    async fn cached_prices(&self) -> EsiResult<Arc<Vec<InsurancePrice>>> {
+        let now = self.esi.now_millis()?;
+        if let Ok(cache) = self.esi.insurance_prices.lock() {
+            if let Some((fetched, prices)) = cache.as_ref() {
+                if now - fetched < INSURANCE_PRICES_TTL.as_millis() as i64 {
+                    return Ok(Arc::clone(prices));
+                }
+            }
+        }
+        let prices = Arc::new(self.get_insurance_prices().await?);
+        if let Ok(mut cache) = self.esi.insurance_prices.lock() {
+            *cache = Some((now, Arc::clone(&prices)));
+        }
+        Ok(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InsurancePrice;
+
+    #[test]
+    fn test_payouts() {
+        let source = r#"{
+            "levels": [
+                { "cost": 1000.0, "name": "Basic", "payout": 10000.0 },
+                { "cost": 30000.0, "name": "Platinum", "payout": 100000.0 },
+                { "cost": 10000.0, "name": "Silver", "payout": 40000.0 }
+            ],
+            "type_id": 587
+        }"#;
+        let price: InsurancePrice = serde_json::from_str(source).unwrap();
+        assert_eq!(price.payout_for("platinum"), Some(100000.0));
+        assert_eq!(price.payout_for("Gold"), None);
+        assert_eq!(price.best_payout(), Some(100000.0));
+    }
+}
@@ -1,31 +1,40 @@
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+
+use crate::groups::{CharacterAffiliation, Position};
 use crate::prelude::*;
+use crate::util::{MAX_CONCURRENT_REQUESTS, MAX_IDS_PER_AFFILIATION_CALL};
 
 /// Endpoints for Killmails
 pub struct KillmailsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct RecentKillMail {
     pub killmail_hash: String,
     pub killmail_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct KillmailAttacker {
     pub alliance_id: Option<i32>,
     pub character_id: Option<i32>,
     pub corporation_id: Option<i32>,
     pub damage_done: i32,
+    pub faction_id: Option<i32>,
     pub final_blow: bool,
     pub security_status: f64,
     pub ship_type_id: Option<i32>,
     pub weapon_type_id: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct KillmailItem {
     pub flag: i32,
@@ -35,7 +44,8 @@ pub struct KillmailItem {
     pub singleton: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct KillmailVictim {
     pub alliance_id: Option<i32>,
@@ -44,28 +54,80 @@ pub struct KillmailVictim {
     pub damage_taken: i32,
     pub faction_id: Option<i32>,
     pub items: Option<Vec<KillmailItem>>,
+    pub position: Option<Position>,
+    pub ship_type_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Killmail {
     pub killmail_id: i32,
-    pub killmail_type: String,
+    pub killmail_time: String,
     pub solar_system_id: i32,
     pub moon_id: Option<i32>,
+    pub war_id: Option<i32>,
     pub attackers: Vec<KillmailAttacker>,
     pub victim: KillmailVictim,
 }
 
+/// Names and current affiliations of the participants in a killmail,
+/// from [`KillmailsGroup::resolve_participants`].
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Default)]
+pub struct KillmailParticipants {
+    /// Names of the characters, corporations, alliances, and factions
+    /// involved, by ID. IDs that ESI did not resolve are missing.
+    pub names: HashMap<i32, String>,
+    /// Current affiliations of the characters involved, by character ID.
+    ///
+    /// These may differ from the corporations and alliances on the
+    /// killmail, which are as of the time of the kill.
+    pub affiliations: HashMap<i32, CharacterAffiliation>,
+}
+
+impl Killmail {
+    /// Get the IDs of the characters involved, and of the corporations,
+    /// alliances, and factions they were in at the time, sorted and
+    /// without duplicates.
+    fn participant_ids(&self) -> (Vec<i32>, Vec<i32>) {
+        let victim = &self.victim;
+        let mut characters: Vec<i32> = self
+            .attackers
+            .iter()
+            .filter_map(|attacker| attacker.character_id)
+            .chain(victim.character_id)
+            .collect();
+        let mut others: Vec<i32> = self
+            .attackers
+            .iter()
+            .flat_map(|attacker| {
+                [
+                    attacker.corporation_id,
+                    attacker.alliance_id,
+                    attacker.faction_id,
+                ]
+            })
+            .chain([victim.corporation_id, victim.alliance_id, victim.faction_id])
+            .flatten()
+            .collect();
+        characters.sort_unstable();
+        characters.dedup();
+        others.sort_unstable();
+        others.dedup();
+        (characters, others)
+    }
+}
+
 impl KillmailsGroup<'_> {
     // NOTE unknown type; I haven't played in a long time
     api_get!(
-        /// Get a character's recent kills & losses.
+        /// Get a page of a character's recent kills & losses.
         get_character_recent,
         "get_characters_character_id_killmails_recent",
         RequestType::Authenticated,
         Vec<RecentKillMail>,
-        (character_id: i32) => "{character_id}"
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
     );
 
     api_get!(
@@ -78,5 +140,119 @@ impl KillmailsGroup<'_> {
         (killmail_hash: &str) => "{killmail_hash}"
     );
 
+    /// Get many killmails from their `(killmail_id, killmail_hash)` pairs.
+    ///
+    /// Killmails are fetched concurrently, with a bounded number of requests
+    /// in flight. The results are in the same order as `pairs`; a failure to
+    /// fetch one killmail does not stop the others from being fetched.
+    pub async fn get_killmails(&self, pairs: &[(i32, &str)]) -> Vec<EsiResult<Killmail>> {
+        stream::iter(pairs)
+            .map(|(killmail_id, killmail_hash)| self.get_killmail(*killmail_id, killmail_hash))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect()
+            .await
+    }
+
+    /// Get the names and current affiliations of everyone in a killmail.
+    ///
+    /// Affiliations are looked up in batches for every character on the
+    /// killmail, and then names are resolved with [`Esi::resolve_names`]
+    /// for the characters, their corporations, alliances, and factions,
+    /// both as of the kill and now.
+    pub async fn resolve_participants(
+        &self,
+        killmail: &Killmail,
+    ) -> EsiResult<KillmailParticipants> {
+        let (characters, others) = killmail.participant_ids();
+        let character_group = self.esi.group_character();
+        let mut affiliations = HashMap::with_capacity(characters.len());
+        for chunk in characters.chunks(MAX_IDS_PER_AFFILIATION_CALL) {
+            let ids: Vec<u64> = chunk.iter().map(|&id| id as u64).collect();
+            let found = character_group.get_affiliation(&ids).await?;
+            affiliations.extend(found.into_iter().map(|a| (a.character_id, a)));
+        }
+        let ids: Vec<i64> = characters
+            .iter()
+            .chain(&others)
+            .copied()
+            .chain(affiliations.values().flat_map(|a| {
+                [Some(a.corporation_id), a.alliance_id, a.faction_id]
+                    .into_iter()
+                    .flatten()
+            }))
+            .map(i64::from)
+            .collect();
+        let names = self
+            .esi
+            .resolve_names(&ids)
+            .await?
+            .into_iter()
+            .map(|(id, resolved)| (id as i32, resolved.name))
+            .collect();
+        Ok(KillmailParticipants {
+            names,
+            affiliations,
+        })
+    }
+
     // more endpoints ...
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Killmail;
+
+    #[test]
+    fn test_participant_ids() {
+        let source = r#"{
+            "killmail_id": 113000000,
+            "killmail_time": "2024-01-01T00:00:00Z",
+            "solar_system_id": 30002057,
+            "attackers": [
+              {
+                "alliance_id": 99000001,
+                "character_id": 2112000001,
+                "corporation_id": 98000001,
+                "damage_done": 1200,
+                "final_blow": true,
+                "security_status": -2.5,
+                "ship_type_id": 17738
+              },
+              {
+                "alliance_id": 99000001,
+                "character_id": 2112000002,
+                "corporation_id": 98000001,
+                "damage_done": 300,
+                "final_blow": false,
+                "security_status": 0.1
+              },
+              {
+                "corporation_id": 1000125,
+                "damage_done": 50,
+                "faction_id": 500010,
+                "final_blow": false,
+                "security_status": 0.0
+              }
+            ],
+            "victim": {
+              "character_id": 2112000003,
+              "corporation_id": 98000002,
+              "damage_taken": 1550,
+              "faction_id": 500001,
+              "position": { "x": 1.5, "y": -2.0, "z": 3.25 },
+              "ship_type_id": 587
+            }
+          }"#;
+        let killmail: Killmail = serde_json::from_str(source).unwrap();
+        assert_eq!(killmail.killmail_time, "2024-01-01T00:00:00Z");
+        assert_eq!(killmail.victim.ship_type_id, 587);
+        assert_eq!(killmail.victim.position.unwrap().z, 3.25);
+        let (characters, others) = killmail.participant_ids();
+
+        assert_eq!(characters, vec![2112000001, 2112000002, 2112000003]);
+        assert_eq!(
+            others,
+            vec![500001, 500010, 1000125, 98000001, 98000002, 99000001]
+        );
+    }
+}
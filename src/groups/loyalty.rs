@@ -1,8 +1,85 @@
-#![allow(unused)]
-
 use crate::prelude::*;
 
 /// Endpoints for Loyalty
 pub struct LoyaltyGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct LoyaltyStoreRequiredItem {
+    pub quantity: i32,
+    pub type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct LoyaltyStoreOffer {
+    pub ak_cost: Option<i32>,
+    pub isk_cost: i64,
+    pub lp_cost: i32,
+    pub offer_id: i32,
+    pub quantity: i32,
+    pub required_items: Vec<LoyaltyStoreRequiredItem>,
+    pub type_id: i32,
+}
+
+impl LoyaltyStoreOffer {
+    /// Whether the offer can be bought with the given LP and ISK.
+    ///
+    /// Required items and analysis kredits are not checked.
+    pub fn is_affordable(&self, available_lp: i32, available_isk: i64) -> bool {
+        self.lp_cost <= available_lp && self.isk_cost <= available_isk
+    }
+}
+
+impl LoyaltyGroup<'_> {
+    api_get!(
+        /// Get the offers in a corporation's loyalty store.
+        get_loyalty_store_offers,
+        "get_loyalty_stores_corporation_id_offers",
+        RequestType::Public,
+        Vec<LoyaltyStoreOffer>,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
+    /// Get the offers in a corporation's loyalty store that can be bought
+    /// with the given LP and ISK.
+    ///
+    /// Required items and analysis kredits are not checked.
+    pub async fn affordable_offers(
+        &self,
+        corporation_id: i32,
+        available_lp: i32,
+        available_isk: i64,
+    ) -> EsiResult<Vec<LoyaltyStoreOffer>> {
+        let offers = self.get_loyalty_store_offers(corporation_id).await?;
+        Ok(offers
+            .into_iter()
+            .filter(|offer| offer.is_affordable(available_lp, available_isk))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoyaltyStoreOffer;
+
+    #[test]
+    fn test_is_affordable() {
+        let source = r#"{
+            "isk_cost": 1000000,
+            "lp_cost": 2400,
+            "offer_id": 3584,
+            "quantity": 5000,
+            "required_items": [{ "quantity": 1, "type_id": 234 }],
+            "type_id": 23047
+        }"#;
+        let offer: LoyaltyStoreOffer = serde_json::from_str(source).unwrap();
+        assert!(offer.is_affordable(2400, 1_000_000));
+        assert!(!offer.is_affordable(2399, 1_000_000));
+        assert!(!offer.is_affordable(5000, 999_999));
+    }
+}
@@ -1,5 +1,3 @@
-#![allow(unused)]
-
 use crate::prelude::*;
 
 /// Endpoints for Mail
@@ -7,7 +5,29 @@ pub struct MailGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct MailRecipient {
+    pub recipient_id: i32,
+    pub recipient_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct MailHeader {
+    pub from: Option<i32>,
+    pub is_read: Option<bool>,
+    pub labels: Option<Vec<i32>>,
+    pub mail_id: Option<i32>,
+    pub recipients: Option<Vec<MailRecipient>>,
+    pub subject: Option<String>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Information about all mail labels.
 pub struct MailLabels {
     /// List of individual mail labels.
@@ -16,7 +36,8 @@ pub struct MailLabels {
     pub unread_count: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 /// Information about an individual mail label.
 pub struct MailLabel {
     /// Color of the label as RGB Hex (`#rrggbb`).
@@ -40,4 +61,110 @@ impl MailGroup<'_> {
         MailLabels,
         (character_id: i32) => "{character_id}"
     );
+
+    /// Return up to 50 of a character's most recent mail headers.
+    ///
+    /// Pass `labels` to only include mail with any of those labels, and
+    /// `last_mail_id` to only include mail older than that ID.
+    pub async fn get_mail_headers(
+        &self,
+        character_id: i32,
+        labels: Option<&[i32]>,
+        last_mail_id: Option<i32>,
+    ) -> EsiResult<Vec<MailHeader>> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_characters_character_id_mail")?
+            .replace("{character_id}", &character_id.to_string());
+        let mut params = Vec::new();
+        if let Some(labels) = labels {
+            let labels: Vec<String> = labels.iter().map(|l| l.to_string()).collect();
+            params.push(("labels", labels.join(",")));
+        }
+        if let Some(last_mail_id) = last_mail_id {
+            params.push(("last_mail_id", last_mail_id.to_string()));
+        }
+        let params: Vec<(&str, &str)> = params.iter().map(|(a, b)| (*a, &**b)).collect();
+        self.esi
+            .query(
+                "GET",
+                RequestType::Authenticated,
+                &path,
+                Some(&params),
+                None,
+            )
+            .await
+    }
+
+    /// Return all of a character's mail headers, newest first.
+    ///
+    /// Walks the `last_mail_id` cursor of [`MailGroup::get_mail_headers`],
+    /// passing the lowest mail ID seen so far, until ESI returns an empty page
+    /// or a page that doesn't move the cursor lower.
+    pub async fn get_all_mail_headers(
+        &self,
+        character_id: i32,
+        labels: Option<&[i32]>,
+    ) -> EsiResult<Vec<MailHeader>> {
+        let mut all = Vec::new();
+        let mut last_mail_id = None;
+        loop {
+            let page = self
+                .get_mail_headers(character_id, labels, last_mail_id)
+                .await?;
+            let Some(min) = page.iter().filter_map(|header| header.mail_id).min() else {
+                break;
+            };
+            // otherwise the same page would be fetched forever
+            if last_mail_id.is_some_and(|last| min >= last) {
+                break;
+            }
+            all.extend(page);
+            last_mail_id = Some(min);
+        }
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_server::{serve, Response};
+
+    #[tokio::test]
+    async fn test_get_all_mail_headers_stops_on_stuck_cursor() {
+        let base_url = serve(|request| {
+            assert_eq!(request.header("authorization"), Some("Bearer abc"));
+            // every page after the first is the same, whatever the cursor
+            if request.target.contains("last_mail_id=") {
+                Response::json(r#"[{"mail_id": 3}]"#)
+            } else {
+                Response::json(r#"[{"mail_id": 5}, {"mail_id": 4}]"#)
+            }
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(serde_json::json!({
+                "paths": {
+                    "/v1/characters/{character_id}/mail/": {
+                        "get": { "operationId": "get_characters_character_id_mail" }
+                    },
+                }
+            })))
+            .access_token(Some("abc"))
+            .access_expiration(Some(60_000))
+            .clock(|| 0)
+            .build()
+            .unwrap();
+
+        let headers = esi
+            .group_mail()
+            .get_all_mail_headers(2112000000, None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = headers.iter().filter_map(|header| header.mail_id).collect();
+        assert_eq!(ids, vec![5, 4, 3]);
+    }
 }
@@ -1,8 +1,11 @@
 #![allow(unused)]
 
+use std::collections::HashMap;
+
 use crate::prelude::*;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct HistoryItem {
     pub average: f64,
@@ -13,7 +16,8 @@ pub struct HistoryItem {
     pub volume: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct MarketOrder {
     pub duration: i32,
@@ -30,7 +34,8 @@ pub struct MarketOrder {
     pub volume_total: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct PriceItem {
     pub adjusted_price: Option<f64>,
@@ -38,7 +43,8 @@ pub struct PriceItem {
     pub type_id: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CharacterOrder {
     pub duration: i32,
@@ -57,6 +63,78 @@ pub struct CharacterOrder {
     pub volume_total: i32,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CharacterOrderHistoryItem {
+    #[serde(flatten)]
+    pub order: CharacterOrder,
+    pub state: String,
+}
+
+/// One of a character's orders, open or closed, from
+/// [`MarketGroup::get_all_character_orders`].
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CharacterOrderEntry {
+    /// The order.
+    pub order: CharacterOrder,
+    /// Whether the order is still open.
+    pub is_open: bool,
+    /// How the order was closed, such as "expired" or "cancelled".
+    /// `None` for open orders.
+    pub state: Option<String>,
+}
+
+/// Merge a character's open and closed orders, newest first.
+fn merge_character_orders(
+    open: Vec<CharacterOrder>,
+    history: Vec<CharacterOrderHistoryItem>,
+) -> Vec<CharacterOrderEntry> {
+    let mut entries: Vec<CharacterOrderEntry> = open
+        .into_iter()
+        .map(|order| CharacterOrderEntry {
+            order,
+            is_open: true,
+            state: None,
+        })
+        .chain(history.into_iter().map(|item| CharacterOrderEntry {
+            order: item.order,
+            is_open: false,
+            state: Some(item.state),
+        }))
+        .collect();
+    // ESI's timestamps all have the same format, so they sort as strings
+    entries.sort_by(|a, b| {
+        b.order
+            .issued
+            .cmp(&a.order.issued)
+            .then(b.order.order_id.cmp(&a.order.order_id))
+    });
+    entries
+}
+
+/// A region's orders for a single type, split into price-sorted sides.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OrderBook {
+    /// Buy orders, highest price first.
+    pub buys: Vec<MarketOrder>,
+    /// Sell orders, lowest price first.
+    pub sells: Vec<MarketOrder>,
+}
+
+impl OrderBook {
+    /// Split orders into buys and sells, each sorted best price first.
+    pub fn from_orders(orders: Vec<MarketOrder>) -> Self {
+        let (mut buys, mut sells): (Vec<_>, Vec<_>) =
+            orders.into_iter().partition(|order| order.is_buy_order);
+        buys.sort_by(|a, b| b.price.total_cmp(&a.price));
+        sells.sort_by(|a, b| a.price.total_cmp(&b.price));
+        Self { buys, sells }
+    }
+}
+
 /// Endpoints for Market
 pub struct MarketGroup<'a> {
     pub(crate) esi: &'a Esi,
@@ -85,6 +163,17 @@ impl MarketGroup<'_> {
         Optional(type_id: i32) => "type_id"
     );
 
+    api_get_paged!(
+        /// Get a page of orders in a region, with the total page count.
+        get_region_orders_paged,
+        "get_markets_region_id_orders",
+        RequestType::Public,
+        MarketOrder,
+        (region_id: i32) => "{region_id}";
+        Optional(order_type: String) => "order_type",
+        Optional(type_id: i32) => "type_id"
+    );
+
     api_get!(
         /// Get a list of average and adjusted prices
         get_market_prices,
@@ -93,6 +182,22 @@ impl MarketGroup<'_> {
         Vec<PriceItem>,
     );
 
+    /// Get the average and adjusted prices of every type, keyed by type ID.
+    ///
+    /// ESI updates these prices about once a day, so the response is
+    /// kept on the `Esi` struct until the time in its `Expires` header.
+    pub async fn get_market_prices_map(&self) -> EsiResult<HashMap<i32, PriceItem>> {
+        let path = self.esi.get_wrapped_endpoint("get_markets_prices")?;
+        let prices: Vec<PriceItem> = self
+            .esi
+            .query_until_expires("GET", RequestType::Public, &path, None)
+            .await?;
+        Ok(prices
+            .into_iter()
+            .map(|price| (price.type_id, price))
+            .collect())
+    }
+
     api_get!(
         /// List open market orders placed by a character
         get_character_orders,
@@ -101,4 +206,162 @@ impl MarketGroup<'_> {
         Vec<CharacterOrder>,
         (character_id: i32) => "{character_id}"
     );
+
+    api_get_paged!(
+        /// Get a page of a character's closed orders from the last 90 days,
+        /// with the total page count.
+        get_character_order_history,
+        "get_characters_character_id_orders_history",
+        RequestType::Authenticated,
+        CharacterOrderHistoryItem,
+        (character_id: i32) => "{character_id}"
+    );
+
+    /// Get all of a character's orders: those that are open, and
+    /// those closed in the last 90 days, newest first.
+    ///
+    /// Every page of the order history is fetched.
+    pub async fn get_all_character_orders(
+        &self,
+        character_id: i32,
+    ) -> EsiResult<Vec<CharacterOrderEntry>> {
+        let open = self.get_character_orders(character_id).await?;
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_characters_character_id_orders_history")?
+            .replace("{character_id}", &character_id.to_string());
+        let history = self
+            .esi
+            .query_all_pages("GET", RequestType::Authenticated, &path, None)
+            .await?;
+        Ok(merge_character_orders(open, history))
+    }
+
+    /// Get the order book for a type in a region.
+    ///
+    /// Every page of orders is fetched.
+    pub async fn get_region_order_book(
+        &self,
+        region_id: i32,
+        type_id: i32,
+    ) -> EsiResult<OrderBook> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_markets_region_id_orders")?
+            .replace("{region_id}", &region_id.to_string());
+        let type_id = type_id.to_string();
+        let orders = self
+            .esi
+            .query_all_pages(
+                "GET",
+                RequestType::Public,
+                &path,
+                Some(&[("order_type", "all"), ("type_id", &type_id)]),
+            )
+            .await?;
+        Ok(OrderBook::from_orders(orders))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        merge_character_orders, CharacterOrder, CharacterOrderHistoryItem, MarketOrder, OrderBook,
+    };
+
+    fn order(order_id: i64, is_buy_order: bool, price: f64) -> MarketOrder {
+        MarketOrder {
+            duration: 90,
+            is_buy_order,
+            issued: "2024-01-01T00:00:00Z".to_owned(),
+            location_id: 60003760,
+            min_volume: 1,
+            order_id,
+            price,
+            range: "region".to_owned(),
+            system_id: 30000142,
+            type_id: 34,
+            volume_remain: 100,
+            volume_total: 100,
+        }
+    }
+
+    #[test]
+    fn test_order_book_sorting() {
+        let book = OrderBook::from_orders(vec![
+            order(1, true, 4.0),
+            order(2, false, 6.0),
+            order(3, true, 5.0),
+            order(4, false, 5.5),
+            order(5, true, 3.0),
+        ]);
+        let ids = |orders: &[MarketOrder]| orders.iter().map(|o| o.order_id).collect::<Vec<_>>();
+
+        assert_eq!(ids(&book.buys), vec![3, 1, 5]);
+        assert_eq!(ids(&book.sells), vec![4, 2]);
+    }
+
+    #[test]
+    fn test_merge_character_orders() {
+        let open: Vec<CharacterOrder> = serde_json::from_str(
+            r#"[{
+                "duration": 90,
+                "escrow": 1000000.0,
+                "is_buy_order": true,
+                "is_corporation": false,
+                "issued": "2024-01-02T00:00:00Z",
+                "location_id": 60003760,
+                "min_volume": 1,
+                "order_id": 3,
+                "price": 10.0,
+                "range": "station",
+                "region_id": 10000002,
+                "type_id": 34,
+                "volume_remain": 100000,
+                "volume_total": 100000
+            }]"#,
+        )
+        .unwrap();
+        let history: Vec<CharacterOrderHistoryItem> = serde_json::from_str(
+            r#"[
+                {
+                  "duration": 30,
+                  "is_corporation": false,
+                  "issued": "2024-01-01T00:00:00Z",
+                  "location_id": 60003760,
+                  "order_id": 1,
+                  "price": 11.0,
+                  "range": "region",
+                  "region_id": 10000002,
+                  "state": "expired",
+                  "type_id": 34,
+                  "volume_remain": 0,
+                  "volume_total": 5000
+                },
+                {
+                  "duration": 30,
+                  "is_corporation": false,
+                  "issued": "2024-01-03T00:00:00Z",
+                  "location_id": 60003760,
+                  "order_id": 2,
+                  "price": 12.0,
+                  "range": "region",
+                  "region_id": 10000002,
+                  "state": "cancelled",
+                  "type_id": 34,
+                  "volume_remain": 20,
+                  "volume_total": 5000
+                }
+            ]"#,
+        )
+        .unwrap();
+        let entries = merge_character_orders(open, history);
+
+        let ids: Vec<i64> = entries.iter().map(|e| e.order.order_id).collect();
+        assert_eq!(ids, vec![2, 3, 1]);
+        assert!(entries[1].is_open);
+        assert_eq!(entries[1].state, None);
+        assert!(!entries[0].is_open);
+        assert_eq!(entries[0].state.as_deref(), Some("cancelled"));
+    }
 }
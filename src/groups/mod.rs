@@ -1,66 +1,272 @@
 //! Endpoint groups
 
+#[cfg(feature = "alliance")]
 mod alliance;
+#[cfg(feature = "alliance")]
 pub use alliance::*;
+#[cfg(feature = "assets")]
 mod assets;
+#[cfg(feature = "assets")]
 pub use assets::*;
+#[cfg(feature = "bookmarks")]
 mod bookmarks;
+#[cfg(feature = "bookmarks")]
 pub use bookmarks::*;
+#[cfg(feature = "calendar")]
 mod calendar;
+#[cfg(feature = "calendar")]
 pub use calendar::*;
+#[cfg(feature = "character")]
 mod character;
+#[cfg(feature = "character")]
 pub use character::*;
+#[cfg(feature = "clones")]
 mod clones;
+#[cfg(feature = "clones")]
 pub use clones::*;
+#[cfg(feature = "contacts")]
 mod contacts;
+#[cfg(feature = "contacts")]
 pub use contacts::*;
+#[cfg(feature = "contracts")]
 mod contracts;
+#[cfg(feature = "contracts")]
 pub use contracts::*;
+#[cfg(feature = "corporation")]
 mod corporation;
+#[cfg(feature = "corporation")]
 pub use corporation::*;
+#[cfg(feature = "dogma")]
 mod dogma;
+#[cfg(feature = "dogma")]
 pub use dogma::*;
+#[cfg(feature = "faction_warfare")]
 mod faction_warfare;
+#[cfg(feature = "faction_warfare")]
 pub use faction_warfare::*;
+#[cfg(feature = "fittings")]
 mod fittings;
+#[cfg(feature = "fittings")]
 pub use fittings::*;
+#[cfg(feature = "fleets")]
 mod fleets;
+#[cfg(feature = "fleets")]
 pub use fleets::*;
+#[cfg(feature = "incursions")]
 mod incursions;
+#[cfg(feature = "incursions")]
 pub use incursions::*;
+#[cfg(feature = "industry")]
 mod industry;
+#[cfg(feature = "industry")]
 pub use industry::*;
+#[cfg(feature = "insurance")]
 mod insurance;
+#[cfg(feature = "insurance")]
 pub use insurance::*;
+#[cfg(feature = "killmails")]
 mod killmails;
+#[cfg(feature = "killmails")]
 pub use killmails::*;
+#[cfg(feature = "location")]
 mod location;
+#[cfg(feature = "location")]
 pub use location::*;
+#[cfg(feature = "loyalty")]
 mod loyalty;
+#[cfg(feature = "loyalty")]
 pub use loyalty::*;
+#[cfg(feature = "mail")]
 mod mail;
+#[cfg(feature = "mail")]
 pub use mail::*;
+#[cfg(feature = "market")]
 mod market;
+#[cfg(feature = "market")]
 pub use market::*;
+#[cfg(feature = "opportunities")]
 mod opportunities;
+#[cfg(feature = "opportunities")]
 pub use opportunities::*;
+#[cfg(feature = "planetary_interaction")]
 mod planetary_interaction;
+#[cfg(feature = "planetary_interaction")]
 pub use planetary_interaction::*;
+mod position;
+pub use position::*;
+#[cfg(feature = "routes")]
 mod routes;
+#[cfg(feature = "routes")]
 pub use routes::*;
+#[cfg(feature = "search")]
 mod search;
+#[cfg(feature = "search")]
 pub use search::*;
+#[cfg(feature = "skills")]
 mod skills;
+#[cfg(feature = "skills")]
 pub use skills::*;
+#[cfg(feature = "sovereignty")]
 mod sovereignty;
+#[cfg(feature = "sovereignty")]
 pub use sovereignty::*;
+#[cfg(feature = "status")]
 mod status;
+#[cfg(feature = "status")]
 pub use status::*;
+#[cfg(feature = "universe")]
 mod universe;
+#[cfg(feature = "universe")]
 pub use universe::*;
+#[cfg(feature = "user_interface")]
 mod user_interface;
+#[cfg(feature = "user_interface")]
 pub use user_interface::*;
+#[cfg(feature = "wallet")]
 mod wallet;
+#[cfg(feature = "wallet")]
 pub use wallet::*;
+#[cfg(feature = "wars")]
 mod wars;
+#[cfg(feature = "wars")]
 pub use wars::*;
+
+/// The `operationId`s of every ESI endpoint that has a wrapper function
+/// in one of the groups.
+///
+/// Any endpoint not listed here can still be called through [`crate::prelude::Esi::query`].
+/// When adding a wrapper function to a group, add its `operationId` here as well.
+pub const WRAPPED_OP_IDS: &[&str] = &[
+    "delete_fleets_fleet_id_squads_squad_id",
+    "delete_fleets_fleet_id_wings_wing_id",
+    "get_alliances",
+    "get_alliances_alliance_id",
+    "get_alliances_alliance_id_corporations",
+    "get_alliances_alliance_id_icons",
+    "get_characters_character_id",
+    "get_characters_character_id_assets",
+    "get_characters_character_id_blueprints",
+    "get_characters_character_id_clones",
+    "get_characters_character_id_contacts",
+    "get_characters_character_id_corporationhistory",
+    "get_characters_character_id_fittings",
+    "get_characters_character_id_implants",
+    "get_characters_character_id_industry_jobs",
+    "get_characters_character_id_killmails_recent",
+    "get_characters_character_id_location",
+    "get_characters_character_id_mail",
+    "get_characters_character_id_mail_labels",
+    "get_characters_character_id_mining",
+    "get_characters_character_id_notifications",
+    "get_characters_character_id_online",
+    "get_characters_character_id_orders",
+    "get_characters_character_id_orders_history",
+    "get_characters_character_id_planets",
+    "get_characters_character_id_planets_planet_id",
+    "get_characters_character_id_portrait",
+    "get_characters_character_id_roles",
+    "get_characters_character_id_search",
+    "get_characters_character_id_ship",
+    "get_characters_character_id_skills",
+    "get_characters_character_id_wallet",
+    "get_characters_character_id_wallet_journal",
+    "get_characters_character_id_wallet_transactions",
+    "get_corporations_corporation_id",
+    "get_corporations_corporation_id_alliancehistory",
+    "get_corporations_corporation_id_assets",
+    "get_corporations_corporation_id_divisions",
+    "get_corporations_corporation_id_members",
+    "get_corporations_corporation_id_membertracking",
+    "get_corporations_corporation_id_roles",
+    "get_corporations_corporation_id_structures",
+    "get_corporations_corporation_id_wallets",
+    "get_corporations_corporation_id_wallets_division_journal",
+    "get_corporations_npccorps",
+    "get_dogma_attributes",
+    "get_dogma_attributes_attribute_id",
+    "get_dogma_effects",
+    "get_dogma_effects_effect_id",
+    "get_fleets_fleet_id_members",
+    "get_fleets_fleet_id_wings",
+    "get_fw_leaderboards",
+    "get_fw_leaderboards_characters",
+    "get_fw_leaderboards_corporations",
+    "get_fw_stats",
+    "get_fw_systems",
+    "get_fw_wars",
+    "get_incursions",
+    "get_industry_systems",
+    "get_insurance_prices",
+    "get_killmails_killmail_id_killmail_hash",
+    "get_loyalty_stores_corporation_id_offers",
+    "get_markets_prices",
+    "get_markets_region_id_history",
+    "get_markets_region_id_orders",
+    "get_route_origin_destination",
+    "get_sovereignty_map",
+    "get_sovereignty_structures",
+    "get_status",
+    "get_universe_categories_category_id",
+    "get_universe_constellations",
+    "get_universe_constellations_constellation_id",
+    "get_universe_groups_group_id",
+    "get_universe_regions",
+    "get_universe_regions_region_id",
+    "get_universe_stations_station_id",
+    "get_universe_structures",
+    "get_universe_structures_structure_id",
+    "get_universe_systems",
+    "get_universe_systems_system_id",
+    "get_universe_types",
+    "get_universe_types_type_id",
+    "get_wars",
+    "get_wars_war_id",
+    "post_characters_affiliation",
+    "post_characters_character_id_assets_locations",
+    "post_characters_character_id_assets_names",
+    "post_characters_character_id_contacts",
+    "post_characters_character_id_fittings",
+    "post_corporations_corporation_id_assets_locations",
+    "post_corporations_corporation_id_assets_names",
+    "post_fleets_fleet_id_wings",
+    "post_fleets_fleet_id_wings_wing_id_squads",
+    "post_ui_openwindow_marketdetails",
+    "post_universe_ids",
+    "post_universe_names",
+    "put_characters_character_id_contacts",
+    "put_fleets_fleet_id_squads_squad_id",
+    "put_fleets_fleet_id_wings_wing_id",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::WRAPPED_OP_IDS;
+    use std::path::PathBuf;
+    use std::{env, fs};
+
+    #[test]
+    fn test_wrapped_op_ids_complete() {
+        let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        dir.push("src/groups");
+        for entry in fs::read_dir(dir).unwrap() {
+            let content = fs::read_to_string(entry.unwrap().path()).unwrap();
+            let op_ids = content.split('"').skip(1).step_by(2).filter(|s| {
+                ["get_", "post_", "put_", "delete_"]
+                    .iter()
+                    .any(|prefix| s.starts_with(prefix))
+                    && s.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+            });
+            for op_id in op_ids {
+                assert!(
+                    WRAPPED_OP_IDS.contains(&op_id),
+                    "'{op_id}' is missing from WRAPPED_OP_IDS"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrapped_op_ids_sorted() {
+        assert!(WRAPPED_OP_IDS.windows(2).all(|w| w[0] < w[1]));
+    }
+}
@@ -1,8 +1,226 @@
-#![allow(unused)]
+use chrono::{DateTime, Utc};
 
 use crate::prelude::*;
+use crate::util::parse_datetime;
 
 /// Endpoints for PlanetaryInteraction
 pub struct PlanetaryInteractionGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct Planet {
+    pub last_update: String,
+    pub num_pins: i32,
+    pub owner_id: i32,
+    pub planet_id: i32,
+    pub planet_type: String,
+    pub solar_system_id: i32,
+    pub upgrade_level: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct PlanetLink {
+    pub destination_pin_id: i64,
+    pub link_level: i32,
+    pub source_pin_id: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct PlanetPinContent {
+    pub amount: i64,
+    pub type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct ExtractorHead {
+    pub head_id: i32,
+    pub latitude: f32,
+    pub longitude: f32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct ExtractorDetails {
+    pub cycle_time: Option<i32>,
+    pub head_radius: Option<f32>,
+    pub heads: Vec<ExtractorHead>,
+    pub product_type_id: Option<i32>,
+    pub qty_per_cycle: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct FactoryDetails {
+    pub schematic_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct PlanetPin {
+    pub contents: Option<Vec<PlanetPinContent>>,
+    pub expiry_time: Option<String>,
+    pub extractor_details: Option<ExtractorDetails>,
+    pub factory_details: Option<FactoryDetails>,
+    pub install_time: Option<String>,
+    pub last_cycle_start: Option<String>,
+    pub latitude: f32,
+    pub longitude: f32,
+    pub pin_id: i64,
+    pub schematic_id: Option<i32>,
+    pub type_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct PlanetRoute {
+    pub content_type_id: i32,
+    pub destination_pin_id: i64,
+    pub quantity: f64,
+    pub route_id: i64,
+    pub source_pin_id: i64,
+    pub waypoints: Option<Vec<i64>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct ColonyLayout {
+    pub links: Vec<PlanetLink>,
+    pub pins: Vec<PlanetPin>,
+    pub routes: Vec<PlanetRoute>,
+}
+
+/// When an extractor pin on a colony stops producing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExtractorExpiry {
+    /// ID of the extractor pin.
+    pub pin_id: i64,
+    /// Type that the extractor produces, if it has a program set.
+    pub product_type_id: Option<i32>,
+    /// When the extractor program ends. `None` if no program is installed.
+    pub expiry_time: Option<DateTime<Utc>>,
+}
+
+impl ColonyLayout {
+    /// Get the expiry time of every extractor in the colony.
+    ///
+    /// Pins that are not extractors are skipped.
+    pub fn extractor_expiries(&self) -> EsiResult<Vec<ExtractorExpiry>> {
+        self.pins
+            .iter()
+            .filter_map(|pin| {
+                pin.extractor_details.as_ref().map(|details| {
+                    Ok(ExtractorExpiry {
+                        pin_id: pin.pin_id,
+                        product_type_id: details.product_type_id,
+                        expiry_time: pin.expiry_time.as_deref().map(parse_datetime).transpose()?,
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl PlanetaryInteractionGroup<'_> {
+    api_get!(
+        /// Get a character's planetary colonies.
+        get_colonies,
+        "get_characters_character_id_planets",
+        RequestType::Authenticated,
+        Vec<Planet>,
+        (character_id: i32) => "{character_id}"
+    );
+
+    api_get!(
+        /// Get the full layout of one of a character's colonies.
+        get_colony_layout,
+        "get_characters_character_id_planets_planet_id",
+        RequestType::Authenticated,
+        ColonyLayout,
+        (character_id: i32) => "{character_id}",
+        (planet_id: i32) => "{planet_id}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColonyLayout;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_extractor_expiries() {
+        let source = r#"{
+            "links": [
+              { "destination_pin_id": 1000000017022, "link_level": 0, "source_pin_id": 1000000017021 }
+            ],
+            "pins": [
+              {
+                "expiry_time": "2024-05-03T10:30:00Z",
+                "extractor_details": {
+                  "cycle_time": 1800,
+                  "head_radius": 0.0095,
+                  "heads": [{ "head_id": 0, "latitude": 1.93, "longitude": 0.86 }],
+                  "product_type_id": 2268,
+                  "qty_per_cycle": 1500
+                },
+                "install_time": "2024-05-01T10:30:00Z",
+                "last_cycle_start": "2024-05-02T10:00:00Z",
+                "latitude": 1.92,
+                "longitude": 0.87,
+                "pin_id": 1000000017021,
+                "type_id": 2848
+              },
+              {
+                "extractor_details": { "heads": [] },
+                "latitude": 1.91,
+                "longitude": 0.85,
+                "pin_id": 1000000017023,
+                "type_id": 2848
+              },
+              {
+                "contents": [{ "amount": 3000, "type_id": 2268 }],
+                "factory_details": { "schematic_id": 127 },
+                "latitude": 1.90,
+                "longitude": 0.88,
+                "pin_id": 1000000017022,
+                "schematic_id": 127,
+                "type_id": 2473
+              }
+            ],
+            "routes": [
+              {
+                "content_type_id": 2268,
+                "destination_pin_id": 1000000017022,
+                "quantity": 1500,
+                "route_id": 4,
+                "source_pin_id": 1000000017021
+              }
+            ]
+          }"#;
+        let layout: ColonyLayout = serde_json::from_str(source).unwrap();
+        let expiries = layout.extractor_expiries().unwrap();
+
+        assert_eq!(expiries.len(), 2);
+        assert_eq!(expiries[0].pin_id, 1000000017021);
+        assert_eq!(expiries[0].product_type_id, Some(2268));
+        assert_eq!(
+            expiries[0].expiry_time,
+            Some(Utc.with_ymd_and_hms(2024, 5, 3, 10, 30, 0).unwrap())
+        );
+        assert_eq!(expiries[1].pin_id, 1000000017023);
+        assert_eq!(expiries[1].expiry_time, None);
+    }
+}
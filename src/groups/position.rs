@@ -0,0 +1,88 @@
+use std::ops::{Add, Sub};
+
+use crate::prelude::*;
+
+/// Meters in a light-year, the unit that jump ranges are given in.
+pub const METERS_PER_LIGHT_YEAR: f64 = 9_460_730_472_580_800.0;
+
+/// A position in space, in meters.
+///
+/// Solar systems, constellations, and regions are positioned in the
+/// universe; planets, stations, and assets in space are positioned within
+/// their solar system.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Position {
+    /// Get the straight-line distance to another position, in meters.
+    pub fn distance_to(&self, other: &Position) -> f64 {
+        let d = *other - *self;
+        (d.x * d.x + d.y * d.y + d.z * d.z).sqrt()
+    }
+
+    /// Get the straight-line distance to another position, in light-years.
+    ///
+    /// Only meaningful between positions in the same frame, such as two
+    /// solar systems.
+    pub fn distance_to_ly(&self, other: &Position) -> f64 {
+        self.distance_to(other) / METERS_PER_LIGHT_YEAR
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+
+    fn add(self, other: Position) -> Position {
+        Position {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl Sub for Position {
+    type Output = Position;
+
+    fn sub(self, other: Position) -> Position {
+        Position {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, METERS_PER_LIGHT_YEAR};
+
+    #[test]
+    fn test_distance() {
+        let a = Position {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        };
+        let b = Position {
+            x: 4.0,
+            y: 6.0,
+            z: 3.0,
+        };
+        assert_eq!(a.distance_to(&b), 5.0);
+        assert_eq!(b.distance_to(&a), 5.0);
+        assert_eq!(a - a, Position::default());
+
+        let far = Position {
+            x: 2.5 * METERS_PER_LIGHT_YEAR,
+            ..Position::default()
+        };
+        assert_eq!(Position::default().distance_to_ly(&far), 2.5);
+    }
+}
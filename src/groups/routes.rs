@@ -1,8 +1,288 @@
-#![allow(unused)]
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::{collections::HashMap, fmt};
 
 use crate::prelude::*;
+use crate::util::MAX_CONCURRENT_REQUESTS;
+
+/// Maximum number of systems that ESI accepts in a route's avoid list.
+const MAX_AVOIDED_SYSTEMS: usize = 100;
 
 /// Endpoints for Routes
 pub struct RoutesGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+/// Route preference, matching the in-game autopilot settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RouteFlag {
+    /// Take the shortest route, regardless of security.
+    #[default]
+    Shortest,
+    /// Prefer high-security systems.
+    Secure,
+    /// Prefer low- and null-security systems.
+    Insecure,
+}
+
+impl fmt::Display for RouteFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RouteFlag::Shortest => "shortest",
+            RouteFlag::Secure => "secure",
+            RouteFlag::Insecure => "insecure",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl RoutesGroup<'_> {
+    api_get!(
+        /// Get the systems on the route between two systems, including
+        /// both the origin and destination.
+        get_route,
+        "get_route_origin_destination",
+        RequestType::Public,
+        Vec<i32>,
+        (origin: i32) => "{origin}",
+        (destination: i32) => "{destination}";
+        Optional(flag: RouteFlag) => "flag"
+    );
+
+    api_get!(
+        /// Get the systems on the route between two systems, like
+        /// [`RoutesGroup::get_route`], without passing through any of
+        /// the systems in `avoid`.
+        get_route_avoiding,
+        "get_route_origin_destination",
+        RequestType::Public,
+        Vec<i32>,
+        (origin: i32) => "{origin}",
+        (destination: i32) => "{destination}";
+        Optional(flag: RouteFlag) => "flag";
+        Joined(avoid: &[i32]) => "avoid"
+    );
+
+    /// Get the route between two systems that avoids any system with a
+    /// displayed security status below `min_security`.
+    ///
+    /// The `flag` is passed along with every route request.
+    ///
+    /// A route is fetched, and any systems on it below the threshold are
+    /// added to the avoid list for the next attempt, until the route is
+    /// clear. The origin and destination themselves are never avoided.
+    /// System security is fetched as needed and cached on the `Esi` struct
+    /// for later calls.
+    ///
+    /// Fails with `NoRouteAvoiding` if more than 100 systems, ESI's limit,
+    /// would need to be avoided, or if ESI routes through a system that
+    /// was already avoided.
+    pub async fn get_route_avoiding_below(
+        &self,
+        origin: i32,
+        destination: i32,
+        flag: Option<RouteFlag>,
+        min_security: f64,
+    ) -> EsiResult<Vec<i32>> {
+        let mut avoid = Vec::new();
+        loop {
+            let route = self
+                .get_route_avoiding(origin, destination, flag, &avoid)
+                .await?;
+            let hops: Vec<i32> = route
+                .iter()
+                .copied()
+                .filter(|&system| system != origin && system != destination)
+                .collect();
+            let security = self.system_security(&hops).await?;
+            let below: Vec<i32> = hops
+                .into_iter()
+                .filter(|system| security[system] < min_security)
+                .collect();
+            if below.is_empty() {
+                return Ok(route);
+            }
+            let new: Vec<i32> = below
+                .into_iter()
+                .filter(|system| !avoid.contains(system))
+                .collect();
+            if new.is_empty() || avoid.len() + new.len() > MAX_AVOIDED_SYSTEMS {
+                return Err(EsiError::NoRouteAvoiding(min_security));
+            }
+            avoid.extend(new);
+        }
+    }
+
+    /// Get the displayed security status of systems, using the
+    /// cache on the `Esi` struct where possible.
+    async fn system_security(&self, systems: &[i32]) -> EsiResult<HashMap<i32, f64>> {
+        let mut known: HashMap<i32, f64> = match self.esi.system_security.lock() {
+            Ok(cache) => systems
+                .iter()
+                .filter_map(|system| cache.get(system).map(|&security| (*system, security)))
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+        let missing: Vec<i32> = systems
+            .iter()
+            .copied()
+            .filter(|system| !known.contains_key(system))
+            .collect();
+        let universe = self.esi.group_universe();
+        let fetched: Vec<(i32, f64)> = stream::iter(missing)
+            .map(|system_id| {
+                let universe = &universe;
+                async move {
+                    let system = universe.get_system(system_id).await?;
+                    Ok::<_, EsiError>((system_id, system.rounded_security()))
+                }
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+        if let Ok(mut cache) = self.esi.system_security.lock() {
+            cache.extend(fetched.iter().copied());
+        }
+        known.extend(fetched);
+        Ok(known)
+    }
+
+    /// Get the full route through a list of waypoints, in order.
+    ///
+    /// A route is fetched for each leg between consecutive waypoints,
+    /// and the legs are joined so that each waypoint appears only once.
+    pub async fn get_multi_route(
+        &self,
+        waypoints: &[i32],
+        flag: Option<RouteFlag>,
+    ) -> EsiResult<Vec<i32>> {
+        let mut legs = Vec::with_capacity(waypoints.len().saturating_sub(1));
+        for pair in waypoints.windows(2) {
+            legs.push(self.get_route(pair[0], pair[1], flag).await?);
+        }
+        if legs.is_empty() {
+            return Ok(waypoints.to_vec());
+        }
+        Ok(stitch_routes(legs))
+    }
+
+    /// Get the number of jumps between each pair of `(origin, destination)` systems.
+    ///
+    /// Routes are fetched concurrently, with a bounded number of requests in
+    /// flight. The results are in the same order as `pairs`. A pair with no
+    /// route between them (such as a wormhole system) has a distance of `None`.
+    pub async fn jump_distances(
+        &self,
+        pairs: &[(i32, i32)],
+        flag: Option<RouteFlag>,
+    ) -> EsiResult<Vec<(i32, i32, Option<usize>)>> {
+        stream::iter(pairs)
+            .map(|&(origin, destination)| async move {
+                let jumps = match self.get_route(origin, destination, flag).await {
+                    Ok(route) => Some(route.len().saturating_sub(1)),
+                    Err(EsiError::InvalidStatusCode { status: 404, .. }) => None,
+                    Err(e) => return Err(e),
+                };
+                Ok((origin, destination, jumps))
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+}
+
+/// Join consecutive route legs, dropping the system shared between each leg.
+fn stitch_routes(legs: Vec<Vec<i32>>) -> Vec<i32> {
+    let mut route: Vec<i32> = Vec::new();
+    for leg in legs {
+        let skip = usize::from(route.last().is_some() && route.last() == leg.first());
+        route.extend(leg.into_iter().skip(skip));
+    }
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stitch_routes, RouteFlag};
+    use crate::prelude::*;
+    use crate::test_server::{serve, Response};
+
+    #[test]
+    fn test_stitch_routes() {
+        let legs = vec![vec![1, 2, 3], vec![3, 4], vec![4, 5, 6]];
+        assert_eq!(stitch_routes(legs), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_stitch_routes_same_system() {
+        let legs = vec![vec![1, 2], vec![2], vec![2, 3]];
+        assert_eq!(stitch_routes(legs), vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_get_route_avoiding_below_gives_up() {
+        let base_url = serve(|request| {
+            let (path, query) = request
+                .target
+                .split_once('?')
+                .unwrap_or((&request.target, ""));
+            let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+            match parts[..] {
+                [_, "route", origin, destination] => {
+                    let avoided = query
+                        .split('&')
+                        .find_map(|param| param.strip_prefix("avoid="))
+                        .map_or(0, |avoid| avoid.replace("%2C", ",").split(',').count());
+                    // to 2, every route has a low-sec system not yet avoided;
+                    // to 3, the route ignores the avoid list; to 4, only
+                    // the secure route is direct
+                    let hop = match destination {
+                        "2" => 1000 + avoided,
+                        "4" if query.contains("flag=secure") => {
+                            return Response::json(format!("[{origin}, {destination}]"));
+                        }
+                        _ => 1000,
+                    };
+                    Response::json(format!("[{origin}, {hop}, {destination}]"))
+                }
+                [_, "universe", "systems", system_id] => Response::json(format!(
+                    r#"{{"constellation_id": 1, "name": "a", "position": {{"x": 0, "y": 0, "z": 0}},
+                        "security_status": 0.1, "system_id": {system_id}}}"#
+                )),
+                _ => Response::json("").status(404),
+            }
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(serde_json::json!({
+                "paths": {
+                    "/v1/route/{origin}/{destination}/": {
+                        "get": { "operationId": "get_route_origin_destination" }
+                    },
+                    "/v4/universe/systems/{system_id}/": {
+                        "get": { "operationId": "get_universe_systems_system_id" }
+                    },
+                }
+            })))
+            .build()
+            .unwrap();
+        let routes = esi.group_routes();
+
+        for destination in [2, 3] {
+            assert!(matches!(
+                routes
+                    .get_route_avoiding_below(1, destination, None, 0.5)
+                    .await,
+                Err(EsiError::NoRouteAvoiding(_))
+            ));
+        }
+        assert_eq!(
+            routes
+                .get_route_avoiding_below(1, 4, Some(RouteFlag::Secure), 0.5)
+                .await
+                .unwrap(),
+            vec![1, 4]
+        );
+    }
+}
@@ -21,7 +21,8 @@ impl SearchGroup<'_> {
     );
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct SearchResult {
     pub agent: Option<Vec<i32>>,
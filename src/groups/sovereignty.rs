@@ -1,4 +1,4 @@
-#![allow(unused)]
+use std::collections::HashMap;
 
 use crate::prelude::*;
 
@@ -6,3 +6,54 @@ use crate::prelude::*;
 pub struct SovereigntyGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct SovMapEntry {
+    pub alliance_id: Option<i32>,
+    pub corporation_id: Option<i32>,
+    pub faction_id: Option<i32>,
+    pub system_id: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct SovStructure {
+    pub alliance_id: i32,
+    pub solar_system_id: i32,
+    pub structure_id: i64,
+    pub structure_type_id: i32,
+    pub vulnerability_occupancy_level: Option<f64>,
+    pub vulnerable_end_time: Option<String>,
+    pub vulnerable_start_time: Option<String>,
+}
+
+impl SovereigntyGroup<'_> {
+    api_get!(
+        /// Get the sovereignty information for all solar systems.
+        get_sovereignty_map,
+        "get_sovereignty_map",
+        RequestType::Public,
+        Vec<SovMapEntry>,
+    );
+
+    api_get!(
+        /// Get a list of all sovereignty structures.
+        get_sovereignty_structures,
+        "get_sovereignty_structures",
+        RequestType::Public,
+        Vec<SovStructure>,
+    );
+
+    /// Get the sovereignty information for all solar systems,
+    /// keyed by `system_id`.
+    pub async fn get_sovereignty_map_by_system(&self) -> EsiResult<HashMap<i32, SovMapEntry>> {
+        let entries = self.get_sovereignty_map().await?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.system_id, entry))
+            .collect())
+    }
+}
@@ -1,8 +1,42 @@
-#![allow(unused)]
-
 use crate::prelude::*;
 
 /// Endpoints for Status
 pub struct StatusGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct ServerStatus {
+    pub players: i32,
+    pub server_version: String,
+    pub start_time: String,
+    pub vip: Option<bool>,
+}
+
+impl StatusGroup<'_> {
+    api_get!(
+        /// Get the current status of the EVE server.
+        ///
+        /// Note that ESI returns a 503 while Tranquility is down.
+        get_status,
+        "get_status",
+        RequestType::Public,
+        ServerStatus,
+    );
+
+    /// Get whether the EVE server is up and open to players.
+    ///
+    /// A 503 from ESI means the server is down, which is returned
+    /// here as `false` rather than an error. A server that is up but
+    /// in VIP mode is also reported as `false`, as regular players
+    /// cannot log in.
+    pub async fn is_online(&self) -> EsiResult<bool> {
+        match self.get_status().await {
+            Ok(status) => Ok(!status.vip.unwrap_or(false)),
+            Err(e) if e.status() == Some(503) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
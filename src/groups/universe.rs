@@ -1,21 +1,22 @@
 #![allow(unused)]
 
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashMap;
+
+use crate::groups::Position;
 use crate::prelude::*;
+use crate::util::MAX_CONCURRENT_REQUESTS;
+
+/// ID of the "Blueprint" item category.
+const BLUEPRINT_CATEGORY_ID: i32 = 9;
 
 /// Endpoints for Universe
 pub struct UniverseGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-#[allow(missing_docs)]
-pub struct Position {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-}
-
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Constellation {
     pub constellation_id: i32,
@@ -25,7 +26,8 @@ pub struct Constellation {
     pub systems: Vec<i32>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Region {
     pub constellations: Vec<i32>,
@@ -34,7 +36,8 @@ pub struct Region {
     pub region_id: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct SystemPlanet {
     pub asteroid_belts: Option<Vec<i32>>,
@@ -42,7 +45,8 @@ pub struct SystemPlanet {
     pub planet_id: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct System {
     pub constellation_id: i32,
@@ -57,7 +61,60 @@ pub struct System {
     pub system_id: i32,
 }
 
-#[derive(Debug, Deserialize)]
+/// Security band of a solar system, as shown in the game client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecurityBand {
+    /// Displayed security of 0.5 or higher.
+    HighSec,
+    /// Displayed security of 0.1 to 0.4.
+    LowSec,
+    /// Displayed security of 0.0 or lower.
+    NullSec,
+}
+
+impl System {
+    /// Get the system's security status rounded to one decimal place
+    /// the way the game client displays it.
+    ///
+    /// Values are rounded half up, except that any positive value below
+    /// 0.05 is shown as 0.1, so that such systems are still low-sec.
+    /// Small negative values round to 0.0 rather than -0.0.
+    pub fn rounded_security(&self) -> f64 {
+        round_security(self.security_status)
+    }
+
+    /// Get the system's security band based on its displayed security status.
+    pub fn security_band(&self) -> SecurityBand {
+        security_band(self.security_status)
+    }
+}
+
+fn round_security(security: f64) -> f64 {
+    if security > 0.0 && security < 0.05 {
+        return 0.1;
+    }
+    let rounded = (security * 10.0 + 0.5).floor() / 10.0;
+    // avoid -0.0
+    if rounded == 0.0 {
+        0.0
+    } else {
+        rounded
+    }
+}
+
+fn security_band(security: f64) -> SecurityBand {
+    let rounded = round_security(security);
+    if rounded >= 0.5 {
+        SecurityBand::HighSec
+    } else if rounded > 0.0 {
+        SecurityBand::LowSec
+    } else {
+        SecurityBand::NullSec
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Ids {
     pub characters: Option<Vec<Category>>,
@@ -69,28 +126,51 @@ pub struct Ids {
     pub stations: Option<Vec<Category>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Category {
     pub id: i32,
     pub name: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct UniverseName {
+    pub category: String,
+    pub id: i64,
+    pub name: String,
+}
+
+/// Name and category resolved for an ID by [`Esi::resolve_names`].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ResolvedName {
+    /// Name of the entity.
+    pub name: String,
+    /// Category of the entity, like "character" or "solar_system".
+    pub category: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct TypeDogmaAttribute {
     pub attribute_id: i32,
     pub value: f64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct TypeDogmaEffect {
     pub effect_id: i32,
     pub is_default: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Type {
     pub capacity: Option<f64>,
@@ -111,7 +191,8 @@ pub struct Type {
     pub volume: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Station {
     pub max_dockable_ship_volume: f64,
@@ -128,7 +209,8 @@ pub struct Station {
     pub type_id: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Structure {
     pub name: String,
@@ -138,7 +220,8 @@ pub struct Structure {
     pub type_id: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct CategoriesCategory {
     pub category_id: i32,
@@ -147,7 +230,8 @@ pub struct CategoriesCategory {
     pub published: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[allow(missing_docs)]
 pub struct Group {
     pub category_id: i32,
@@ -237,11 +321,13 @@ impl UniverseGroup<'_> {
     );
 
     api_get!(
-        /// Get a list of type ids
+        /// Get a page of type ids
         get_type_ids,
         "get_universe_types",
         RequestType::Public,
         Vec<i32>,
+        ;
+        Optional(page: i32) => "page"
     );
 
     api_get!(
@@ -253,6 +339,45 @@ impl UniverseGroup<'_> {
         (type_id: i32) => "{type_id}"
     );
 
+    /// Get every type id, fetching every page.
+    pub async fn get_all_type_ids(&self) -> EsiResult<Vec<i32>> {
+        let path = self.esi.get_wrapped_endpoint("get_universe_types")?;
+        self.esi
+            .query_all_pages("GET", RequestType::Public, &path, None)
+            .await
+    }
+
+    /// Get information on every type, keyed by type id.
+    ///
+    /// Fetches the ids with [`UniverseGroup::get_all_type_ids`], then makes
+    /// one request per type, so expect it to make tens of thousands of
+    /// requests. The number in flight at once is bounded. Unlike
+    /// [`UniverseGroup::get_types`], which carries on past failures, the
+    /// first failure stops any further types from being fetched and is
+    /// returned, so a run of errors won't eat through ESI's error limit.
+    /// Store the result rather than calling this often.
+    pub async fn build_type_cache(&self) -> EsiResult<HashMap<i32, Type>> {
+        let type_ids = self.get_all_type_ids().await?;
+        stream::iter(type_ids)
+            .map(|type_id| async move { Ok((type_id, self.get_type(type_id).await?)) })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    /// Get information on many types.
+    ///
+    /// Types are fetched concurrently, with a bounded number of requests
+    /// in flight. The results are in the same order as `type_ids`; a failure
+    /// to fetch one type does not stop the others from being fetched.
+    pub async fn get_types(&self, type_ids: &[i32]) -> Vec<EsiResult<Type>> {
+        stream::iter(type_ids)
+            .map(|&type_id| self.get_type(type_id))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect()
+            .await
+    }
+
     api_get!(
         /// Information about a station
         get_station,
@@ -263,7 +388,7 @@ impl UniverseGroup<'_> {
     );
 
     api_get!(
-        /// Returns information on requested structure if you are on the ACL. Otherwise, returns “Forbidden” for all inputs.
+        /// Returns information on requested structure if you are on the ACL. Otherwise, returns `EsiError::Forbidden` for all inputs.
         get_structure,
         "get_universe_structures_structure_id",
         RequestType::Authenticated,
@@ -271,6 +396,19 @@ impl UniverseGroup<'_> {
         (structure_id: i64) => "{structure_id}"
     );
 
+    api_get!(
+        /// List the IDs of all public structures.
+        ///
+        /// Pass a `filter` of `"market"` or `"manufacturing_basic"` to only
+        /// list structures with that service online.
+        get_structure_ids,
+        "get_universe_structures",
+        RequestType::Public,
+        Vec<i64>,
+        ;
+        Optional(filter: &str) => "filter"
+    );
+
     api_post!(
         /// Get IDs from a list of names
         get_ids,
@@ -280,4 +418,51 @@ impl UniverseGroup<'_> {
         ,
         names: &[&str],
     );
+
+    api_post!(
+        /// Get names and categories for a list of IDs.
+        ///
+        /// ESI accepts at most 1,000 IDs per call.
+        get_names,
+        "post_universe_names",
+        RequestType::Public,
+        Vec<UniverseName>,
+        ,
+        ids: &[i64],
+    );
+
+    /// Get whether a type is a blueprint, from the category of its group.
+    pub async fn is_blueprint_type(&self, type_id: i32) -> EsiResult<bool> {
+        let item_type = self.get_universe_types_type(type_id).await?;
+        let group = self.get_universe_groups_group(item_type.group_id).await?;
+        Ok(group.category_id == BLUEPRINT_CATEGORY_ID)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{round_security, security_band, SecurityBand};
+
+    #[test]
+    fn test_round_security() {
+        assert_eq!(round_security(1.0), 1.0);
+        assert_eq!(round_security(0.45), 0.5);
+        assert_eq!(round_security(0.4499), 0.4);
+        assert_eq!(round_security(0.05), 0.1);
+        assert_eq!(round_security(0.0001), 0.1);
+        assert_eq!(round_security(0.0), 0.0);
+        assert_eq!(round_security(-0.04), 0.0);
+        assert!(round_security(-0.04).is_sign_positive());
+        assert_eq!(round_security(-0.46), -0.5);
+        assert_eq!(round_security(-0.99), -1.0);
+    }
+
+    #[test]
+    fn test_security_band() {
+        assert_eq!(security_band(0.45), SecurityBand::HighSec);
+        assert_eq!(security_band(0.4499), SecurityBand::LowSec);
+        assert_eq!(security_band(0.01), SecurityBand::LowSec);
+        assert_eq!(security_band(0.0), SecurityBand::NullSec);
+        assert_eq!(security_band(-0.2), SecurityBand::NullSec);
+    }
 }
@@ -6,20 +6,94 @@ pub struct UserInterfaceGroup<'a> {
 }
 
 impl UserInterfaceGroup<'_> {
-    /// Open the market details window.
-    pub async fn open_market_details_window(
-        &self,
-        character_id: u64,
-        type_id: i32,
-    ) -> EsiResult<()> {
+    /// Open the market details window for a type, in the game client of
+    /// the authenticated character.
+    ///
+    /// Returns [`EsiError::ClientNotRunning`] if the character is not
+    /// logged into the game.
+    pub async fn open_market_details_window(&self, type_id: i32) -> EsiResult<()> {
         // not using the macro since it doesn't like no body
         let path = self
             .esi
-            .get_endpoint_for_op_id("post_ui_openwindow_marketdetails")?
-            .replace("{character_id}", &character_id.to_string())
-            .replace("{type_id}", &type_id.to_string());
+            .get_wrapped_endpoint("post_ui_openwindow_marketdetails")?;
+        let type_id = type_id.to_string();
         self.esi
-            .query("GET", RequestType::Authenticated, &path, None, None)
+            .query(
+                "POST",
+                RequestType::Authenticated,
+                &path,
+                Some(&[("type_id", &type_id)]),
+                None,
+            )
             .await
+            .map_err(client_not_running)
+    }
+}
+
+/// Map a 404 from a UI endpoint to [`EsiError::ClientNotRunning`], as
+/// that's what ESI returns when there's no game client to act on.
+fn client_not_running(e: EsiError) -> EsiError {
+    if e.status() == Some(404) {
+        EsiError::ClientNotRunning {
+            request_id: e.request_id().map(|id| id.to_owned()),
+        }
+    } else {
+        e
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_server::{serve, Response};
+
+    #[tokio::test]
+    async fn test_open_market_details_window() {
+        let base_url = serve(|request| {
+            assert_eq!(request.method, "POST");
+            let (path, query) = request.target.split_once('?').unwrap();
+            assert!(path.ends_with("/ui/openwindow/marketdetails/"));
+            assert!(query.split('&').any(|param| param == "type_id=34"));
+            if request.header("authorization") == Some("Bearer abc") {
+                Response::json("").status(204)
+            } else {
+                Response::json(r#"{"error":"Not found"}"#)
+                    .status(404)
+                    .header("x-esi-request-id", "req-1")
+            }
+        })
+        .await;
+        let build = |token: &str| {
+            EsiBuilder::new()
+                .user_agent("d")
+                .base_api_url(&base_url)
+                .spec(Some(serde_json::json!({
+                    "paths": {
+                        "/v1/ui/openwindow/marketdetails/": {
+                            "post": { "operationId": "post_ui_openwindow_marketdetails" }
+                        },
+                    }
+                })))
+                .access_token(Some(token))
+                .access_expiration(Some(60_000))
+                .clock(|| 0)
+                .build()
+                .unwrap()
+        };
+
+        let esi = build("abc");
+        esi.group_user_interface()
+            .open_market_details_window(34)
+            .await
+            .unwrap();
+
+        let esi = build("offline");
+        let err = esi
+            .group_user_interface()
+            .open_market_details_window(34)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EsiError::ClientNotRunning { .. }));
+        assert_eq!(err.request_id(), Some("req-1"));
     }
 }
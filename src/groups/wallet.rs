@@ -1,5 +1,6 @@
 #![allow(unused)]
 
+use crate::groups::CorporationDivision;
 use crate::prelude::*;
 
 /// Endpoints for Wallet
@@ -7,6 +8,178 @@ pub struct WalletGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
 
+/// Type of a wallet journal entry.
+///
+/// Values that ESI adds after this list was written are kept as `Unknown`.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+#[allow(missing_docs)]
+pub enum WalletRefType {
+    AccelerationGateFee,
+    AdvertisementListingFee,
+    AgentDonation,
+    AgentLocationServices,
+    AgentMiscellaneous,
+    AgentMissionCollateralPaid,
+    AgentMissionCollateralRefunded,
+    AgentMissionReward,
+    AgentMissionRewardCorporationTax,
+    AgentMissionTimeBonusReward,
+    AgentMissionTimeBonusRewardCorporationTax,
+    AgentSecurityServices,
+    AgentServicesRendered,
+    AgentsPreward,
+    AllianceMaintainanceFee,
+    AllianceRegistrationFee,
+    AssetSafetyRecoveryTax,
+    Bounty,
+    BountyPrize,
+    BountyPrizeCorporationTax,
+    BountyPrizes,
+    BountyReimbursement,
+    BountySurcharge,
+    BrokersFee,
+    CloneActivation,
+    CloneTransfer,
+    ContrabandFine,
+    ContractAuctionBid,
+    ContractAuctionBidCorp,
+    ContractAuctionBidRefund,
+    ContractAuctionSold,
+    ContractBrokersFee,
+    ContractBrokersFeeCorp,
+    ContractCollateral,
+    ContractCollateralDepositedCorp,
+    ContractCollateralPayout,
+    ContractCollateralRefund,
+    ContractDeposit,
+    ContractDepositCorp,
+    ContractDepositRefund,
+    ContractDepositSalesTax,
+    ContractPrice,
+    ContractPricePaymentCorp,
+    ContractReversal,
+    ContractReward,
+    ContractRewardDeposited,
+    ContractRewardDepositedCorp,
+    ContractRewardRefund,
+    ContractSalesTax,
+    Copying,
+    CorporateRewardPayout,
+    CorporateRewardTax,
+    CorporationAccountWithdrawal,
+    CorporationBulkPayment,
+    CorporationDividendPayment,
+    CorporationLiquidation,
+    CorporationLogoChangeCost,
+    CorporationPayment,
+    CorporationRegistrationFee,
+    CourierMissionEscrow,
+    Cspa,
+    Cspaofflinerefund,
+    DatacoreFee,
+    DnaModificationFee,
+    DockingFee,
+    DuelWagerEscrow,
+    DuelWagerPayment,
+    DuelWagerRefund,
+    EssEscrowTransfer,
+    FactorySlotRentalFee,
+    GmCashTransfer,
+    IndustryJobTax,
+    InfrastructureHubMaintenance,
+    Inheritance,
+    Insurance,
+    ItemTraderPayment,
+    JumpCloneActivationFee,
+    JumpCloneInstallationFee,
+    KillRightFee,
+    LpStore,
+    Manufacturing,
+    MarketEscrow,
+    MarketFinePaid,
+    MarketTransaction,
+    MedalCreation,
+    MedalIssued,
+    MissionCompletion,
+    MissionCost,
+    MissionExpiration,
+    MissionReward,
+    OfficeRentalFee,
+    OperationBonus,
+    OpportunityReward,
+    PlanetaryConstruction,
+    PlanetaryExportTax,
+    PlanetaryImportTax,
+    PlayerDonation,
+    PlayerTrading,
+    ProjectDiscoveryReward,
+    ProjectDiscoveryTax,
+    Reaction,
+    ReleaseOfImpoundedProperty,
+    RepairBill,
+    ReprocessingTax,
+    ResearchingMaterialProductivity,
+    ResearchingTechnology,
+    ResearchingTimeProductivity,
+    ResourceWarsReward,
+    ReverseEngineering,
+    SecurityProcessingFee,
+    Shares,
+    SkillPurchase,
+    SovereignityBill,
+    StorePurchase,
+    StorePurchaseRefund,
+    StructureGateJump,
+    TransactionTax,
+    UpkeepAdjustmentFee,
+    WarAllyContract,
+    WarFee,
+    WarFeeSurrender,
+    /// A value not in the documented list.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct WalletJournalEntry {
+    pub amount: Option<f64>,
+    pub balance: Option<f64>,
+    pub context_id: Option<i64>,
+    pub context_id_type: Option<String>,
+    pub date: String,
+    pub description: String,
+    pub first_party_id: Option<i32>,
+    pub id: i64,
+    pub reason: Option<String>,
+    pub ref_type: WalletRefType,
+    pub second_party_id: Option<i32>,
+    pub tax: Option<f64>,
+    pub tax_receiver_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct CorporationWallet {
+    pub balance: f64,
+    pub division: i32,
+}
+
+/// A corporation wallet division's balance along with its name.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamedCorporationWallet {
+    /// The wallet division and its balance.
+    pub wallet: CorporationWallet,
+    /// The name of the division, if it has been given one. The first
+    /// division, the master wallet, usually has no name.
+    pub name: Option<String>,
+}
+
 impl WalletGroup<'_> {
     api_get!(
         /// Returns a character’s wallet balance
@@ -16,4 +189,218 @@ impl WalletGroup<'_> {
         f64,
         (character_id: i32) => "{character_id}"
     );
+
+    api_get!(
+        /// Get the balance of each of a corporation's wallet divisions.
+        ///
+        /// Requires the auth'd character to have the accountant or junior
+        /// accountant role in the corp.
+        get_corporation_wallets,
+        "get_corporations_corporation_id_wallets",
+        RequestType::Authenticated,
+        Vec<CorporationWallet>,
+        (corporation_id: i32) => "{corporation_id}"
+    );
+
+    /// Get the balance of each of a corporation's wallet divisions, along
+    /// with the division's name.
+    ///
+    /// The names come from the corporation's divisions, which requires
+    /// the auth'd character to be a director of the corp.
+    pub async fn get_corporation_wallets_named(
+        &self,
+        corporation_id: i32,
+    ) -> EsiResult<Vec<NamedCorporationWallet>> {
+        let wallets = self.get_corporation_wallets(corporation_id).await?;
+        let divisions = self
+            .esi
+            .group_corporation()
+            .get_divisions(corporation_id)
+            .await?;
+        Ok(name_wallets(
+            wallets,
+            divisions.wallet.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    api_get!(
+        /// Get a page of a character's wallet journal.
+        get_wallet_journal,
+        "get_characters_character_id_wallet_journal",
+        RequestType::Authenticated,
+        Vec<WalletJournalEntry>,
+        (character_id: i32) => "{character_id}";
+        Optional(page: i32) => "page"
+    );
+
+    /// Get every page of a character's wallet journal, sorted oldest-first.
+    ///
+    /// ESI returns the journal newest-first, one page at a time; this
+    /// fetches all of them and sorts the entries by date, then by ID.
+    pub async fn get_wallet_journal_all(
+        &self,
+        character_id: i32,
+    ) -> EsiResult<Vec<WalletJournalEntry>> {
+        let path = self
+            .esi
+            .get_wrapped_endpoint("get_characters_character_id_wallet_journal")?
+            .replace("{character_id}", &character_id.to_string());
+        let mut entries: Vec<WalletJournalEntry> = self
+            .esi
+            .query_all_pages("GET", RequestType::Authenticated, &path, None)
+            .await?;
+        sort_oldest_first(&mut entries);
+        Ok(entries)
+    }
+
+    api_get_paged!(
+        /// Get a page of a character's wallet journal, with the total page count.
+        get_wallet_journal_paged,
+        "get_characters_character_id_wallet_journal",
+        RequestType::Authenticated,
+        WalletJournalEntry,
+        (character_id: i32) => "{character_id}"
+    );
+
+    api_get!(
+        /// Get a page of the journal for one of a corporation's wallet divisions.
+        ///
+        /// Requires the auth'd character to have the accountant or junior
+        /// accountant role in the corp.
+        get_corporation_wallet_journal,
+        "get_corporations_corporation_id_wallets_division_journal",
+        RequestType::Authenticated,
+        Vec<WalletJournalEntry>,
+        (corporation_id: i32) => "{corporation_id}",
+        (division: i32) => "{division}";
+        Optional(page: i32) => "page"
+    );
+
+    api_get_paged!(
+        /// Get a page of the journal for one of a corporation's wallet
+        /// divisions, with the total page count.
+        ///
+        /// Requires the auth'd character to have the accountant or junior
+        /// accountant role in the corp.
+        get_corporation_wallet_journal_paged,
+        "get_corporations_corporation_id_wallets_division_journal",
+        RequestType::Authenticated,
+        WalletJournalEntry,
+        (corporation_id: i32) => "{corporation_id}",
+        (division: i32) => "{division}"
+    );
+}
+
+/// Match wallet balances to their division names by division number.
+fn name_wallets(
+    wallets: Vec<CorporationWallet>,
+    divisions: &[CorporationDivision],
+) -> Vec<NamedCorporationWallet> {
+    wallets
+        .into_iter()
+        .map(|wallet| NamedCorporationWallet {
+            name: divisions
+                .iter()
+                .find(|division| division.division == Some(wallet.division))
+                .and_then(|division| division.name.clone()),
+            wallet,
+        })
+        .collect()
+}
+
+/// Sort journal entries by date, then by ID, oldest first.
+///
+/// ESI dates are all in the same RFC 3339 format, so they sort as strings.
+fn sort_oldest_first(entries: &mut [WalletJournalEntry]) {
+    entries.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        name_wallets, sort_oldest_first, CorporationWallet, WalletJournalEntry, WalletRefType,
+    };
+    use crate::groups::CorporationDivisions;
+
+    #[test]
+    fn test_wallet_ref_type_deserialize() {
+        let ref_types: Vec<WalletRefType> = serde_json::from_str(
+            r#"["player_trading", "market_transaction", "bounty_prizes",
+                "corporation_account_withdrawal", "cspa", "some_new_type"]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            ref_types,
+            vec![
+                WalletRefType::PlayerTrading,
+                WalletRefType::MarketTransaction,
+                WalletRefType::BountyPrizes,
+                WalletRefType::CorporationAccountWithdrawal,
+                WalletRefType::Cspa,
+                WalletRefType::Unknown("some_new_type".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wallet_journal_entry_deserialize() {
+        let source = r#"{
+            "amount": -100000,
+            "balance": 500000.4316,
+            "context_id": 4,
+            "context_id_type": "contract_id",
+            "date": "2018-02-23T14:31:32Z",
+            "description": "Contract Deposit",
+            "first_party_id": 2112625428,
+            "id": 89,
+            "ref_type": "contract_deposit",
+            "second_party_id": 1000132
+        }"#;
+        let entry: WalletJournalEntry = serde_json::from_str(source).unwrap();
+        assert_eq!(entry.ref_type, WalletRefType::ContractDeposit);
+        assert_eq!(
+            serde_json::to_string(&WalletRefType::Unknown("x".to_owned())).unwrap(),
+            r#""x""#
+        );
+    }
+
+    #[test]
+    fn test_sort_oldest_first() {
+        let entry = |id: i64, date: &str| -> WalletJournalEntry {
+            serde_json::from_value(serde_json::json!({
+                "date": date,
+                "description": "",
+                "id": id,
+                "ref_type": "player_donation",
+            }))
+            .unwrap()
+        };
+        let mut entries = vec![
+            entry(3, "2024-01-02T00:00:00Z"),
+            entry(2, "2024-01-01T00:00:00Z"),
+            entry(1, "2024-01-01T00:00:00Z"),
+        ];
+        sort_oldest_first(&mut entries);
+        let ids: Vec<i64> = entries.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_name_wallets() {
+        let wallets: Vec<CorporationWallet> = serde_json::from_str(
+            r#"[{ "balance": 100.0, "division": 1 }, { "balance": 25.5, "division": 2 }]"#,
+        )
+        .unwrap();
+        let divisions: CorporationDivisions = serde_json::from_str(
+            r#"{
+                "hangar": [{ "division": 1, "name": "Ships" }],
+                "wallet": [{ "division": 1 }, { "division": 2, "name": "SRP" }]
+            }"#,
+        )
+        .unwrap();
+        let named = name_wallets(wallets, divisions.wallet.as_deref().unwrap());
+        assert_eq!(named[0].name, None);
+        assert_eq!(named[1].name.as_deref(), Some("SRP"));
+        assert_eq!(named[1].wallet.balance, 25.5);
+    }
 }
@@ -1,8 +1,119 @@
-#![allow(unused)]
-
 use crate::prelude::*;
 
 /// Endpoints for Wars
 pub struct WarsGroup<'a> {
     pub(crate) esi: &'a Esi,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct WarParticipant {
+    pub alliance_id: Option<i32>,
+    pub corporation_id: Option<i32>,
+    pub isk_destroyed: f64,
+    pub ships_killed: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct WarAlly {
+    pub alliance_id: Option<i32>,
+    pub corporation_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[allow(missing_docs)]
+pub struct War {
+    pub aggressor: WarParticipant,
+    pub allies: Option<Vec<WarAlly>>,
+    pub declared: String,
+    pub defender: WarParticipant,
+    pub finished: Option<String>,
+    pub id: i32,
+    pub mutual: bool,
+    pub open_for_allies: bool,
+    pub retracted: Option<String>,
+    pub started: Option<String>,
+}
+
+impl WarsGroup<'_> {
+    api_get!(
+        /// Get a page of war IDs, in descending order.
+        ///
+        /// Only wars with an ID below `max_war_id` are returned, so to
+        /// get the next page, pass the smallest ID from the previous one.
+        get_wars,
+        "get_wars",
+        RequestType::Public,
+        Vec<i32>,
+        ;
+        Optional(max_war_id: i32) => "max_war_id"
+    );
+
+    api_get!(
+        /// Get information about a war.
+        get_war,
+        "get_wars_war_id",
+        RequestType::Public,
+        War,
+        (war_id: i32) => "{war_id}"
+    );
+
+    /// Get every war ID, in descending order.
+    ///
+    /// Walks the `max_war_id` cursor of [`WarsGroup::get_wars`]
+    /// until ESI returns an empty page, or a page that doesn't move
+    /// the cursor lower.
+    pub async fn get_all_wars(&self) -> EsiResult<Vec<i32>> {
+        let mut all = Vec::new();
+        let mut max_war_id = None;
+        loop {
+            let page = self.get_wars(max_war_id).await?;
+            let Some(&min) = page.iter().min() else {
+                break;
+            };
+            // otherwise the same page would be fetched forever
+            if max_war_id.is_some_and(|max| min >= max) {
+                break;
+            }
+            all.extend(page);
+            max_war_id = Some(min);
+        }
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::test_server::{serve, Response};
+
+    #[tokio::test]
+    async fn test_get_all_wars_stops_on_stuck_cursor() {
+        let base_url = serve(|request| {
+            // every page after the first is the same, whatever the cursor
+            if request.target.contains("max_war_id=") {
+                Response::json("[3]")
+            } else {
+                Response::json("[5, 4]")
+            }
+        })
+        .await;
+        let esi = EsiBuilder::new()
+            .user_agent("d")
+            .base_api_url(&base_url)
+            .spec(Some(serde_json::json!({
+                "paths": {
+                    "/v1/wars/": { "get": { "operationId": "get_wars" } },
+                }
+            })))
+            .build()
+            .unwrap();
+
+        let wars = esi.group_wars().get_all_wars().await.unwrap();
+        assert_eq!(wars, vec![5, 4, 3]);
+    }
+}
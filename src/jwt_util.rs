@@ -1,23 +1,28 @@
 use jsonwebtoken::jwk::Jwk;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, TokenData, Validation};
 use log::error;
-use reqwest::Client;
+use reqwest::{Client, Url};
 use serde_json::Value;
 
+use crate::client::login_host;
 use crate::prelude::*;
 
-const TOKEN_AUTH_INFO_URL: &str =
-    "https://login.eveonline.com/.well-known/oauth-authorization-server";
+/// Path on the SSO server of its OAuth metadata document.
+const TOKEN_AUTH_INFO_PATH: &str = "/.well-known/oauth-authorization-server";
 
-/// Get the URL that hosts the valid JWT signing keys.
-async fn get_keys_url(client: &Client) -> EsiResult<String> {
-    let resp = client.get(TOKEN_AUTH_INFO_URL).send().await?;
+/// Get the URL that hosts the valid JWT signing keys, from the
+/// SSO server that `token_url` belongs to.
+async fn get_keys_url(client: &Client, token_url: &Url) -> EsiResult<String> {
+    let info_url = token_url
+        .join(TOKEN_AUTH_INFO_PATH)
+        .map_err(|_| EsiError::InvalidTokenUrl(token_url.to_string()))?;
+    let resp = client.get(info_url).send().await?;
     if resp.status() != 200 {
         error!(
             "Got status {} when making call to get token info",
             resp.status()
         );
-        return Err(EsiError::InvalidStatusCode(resp.status().as_u16()));
+        return Err(EsiError::from_response(&resp));
     }
     let data: Value = resp.json().await?;
     let url = data["jwks_uri"]
@@ -27,8 +32,8 @@ async fn get_keys_url(client: &Client) -> EsiResult<String> {
 }
 
 /// Get the RS256 key to use.
-async fn get_rs256_key(client: &Client) -> EsiResult<String> {
-    let keys_url = get_keys_url(client).await?;
+async fn get_rs256_key(client: &Client, token_url: &Url) -> EsiResult<String> {
+    let keys_url = get_keys_url(client, token_url).await?;
     let resp = client.get(&keys_url).send().await?;
     let data: Value = resp.json().await?;
     let key = data["keys"]
@@ -42,10 +47,12 @@ async fn get_rs256_key(client: &Client) -> EsiResult<String> {
     Ok(key)
 }
 
-/// Decode and validate the JWT token
+/// Decode and validate the JWT token, which must have been issued by
+/// the SSO server at `login_host`.
 fn validate(
     token: &str,
     client_id: &str,
+    login_host: &str,
     decoding_key: &DecodingKey,
 ) -> Result<TokenClaims, EsiError> {
     let mut validations = Validation::new(Algorithm::RS256);
@@ -55,9 +62,8 @@ fn validate(
 
     let token: TokenData<Value> = decode(token, decoding_key, &validations)?;
     /* Additional verifications from https://docs.esi.evetech.net/docs/sso/validating_eve_jwt.html */
-    if token.claims["iss"].as_str().unwrap() != "login.eveonline.com"
-        && token.claims["iss"].as_str().unwrap() != "https://login.eveonline.com"
-    {
+    let issuer = token.claims["iss"].as_str().unwrap_or_default();
+    if issuer != login_host && issuer != format!("https://{login_host}") {
         return Err(EsiError::InvalidJWT(String::from(
             "JWT issuer is incorrect",
         )));
@@ -68,16 +74,20 @@ fn validate(
 }
 
 /// Decode and validate the SSO JWT, returning the contents.
+///
+/// The signing keys are fetched from, and the token must have been
+/// issued by, the SSO server that `token_url` belongs to.
 pub(crate) async fn validate_jwt(
     client: &Client,
     token: &str,
     client_id: &str,
+    token_url: &Url,
 ) -> EsiResult<TokenClaims> {
-    let validation_key_str = get_rs256_key(client).await?;
+    let validation_key_str = get_rs256_key(client, token_url).await?;
     let validation_key: Jwk = serde_json::from_str(&validation_key_str)?;
     let decoding_key = DecodingKey::from_jwk(&validation_key)?;
 
-    validate(token, client_id, &decoding_key)
+    validate(token, client_id, &login_host(token_url), &decoding_key)
 }
 
 #[cfg(test)]
@@ -102,7 +112,8 @@ mod tests {
 
         let decoding_key = DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap();
 
-        let decoded_claim = validate(&token, &client_id, &decoding_key).unwrap();
+        let decoded_claim =
+            validate(&token, &client_id, "login.eveonline.com", &decoding_key).unwrap();
 
         assert_eq!(decoded_claim, claim);
     }
@@ -119,7 +130,7 @@ mod tests {
 
         let decoding_key = DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap();
 
-        assert!(validate(&token, &client_id, &decoding_key).is_err())
+        assert!(validate(&token, &client_id, "login.eveonline.com", &decoding_key).is_err())
     }
 
     #[test]
@@ -134,7 +145,7 @@ mod tests {
 
         let decoding_key = DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap();
 
-        assert!(validate(&token, &client_id, &decoding_key).is_err())
+        assert!(validate(&token, &client_id, "login.eveonline.com", &decoding_key).is_err())
     }
 
     #[test]
@@ -149,7 +160,31 @@ mod tests {
 
         let decoding_key = DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap();
 
-        assert!(validate(&token, &client_id, &decoding_key).is_err())
+        assert!(validate(&token, &client_id, "login.eveonline.com", &decoding_key).is_err())
+    }
+
+    #[test]
+    fn test_jwt_validity_singularity() {
+        let header = Header::new(Algorithm::RS256);
+        let (mut claim, client_id) = generate_valid_claims();
+        claim.iss = "https://sisilogin.testeveonline.com".to_string();
+        claim.tenant = "singularity".to_string();
+        let (private_key, public_key) = load_key();
+
+        let encoding_key = EncodingKey::from_rsa_pem(private_key.as_bytes()).unwrap();
+        let token = jsonwebtoken::encode(&header, &claim, &encoding_key).unwrap();
+
+        let decoding_key = DecodingKey::from_rsa_pem(public_key.as_bytes()).unwrap();
+
+        let decoded_claim = validate(
+            &token,
+            &client_id,
+            "sisilogin.testeveonline.com",
+            &decoding_key,
+        )
+        .unwrap();
+        assert_eq!(decoded_claim, claim);
+        assert!(validate(&token, &client_id, "login.eveonline.com", &decoding_key).is_err());
     }
 
     fn generate_valid_claims() -> (TokenClaims, String) {
@@ -31,16 +31,15 @@
 //!     esi.get_authorize_url().unwrap()
 //! }
 //!
-//! async fn authenticate_user(esi: &mut Esi, code: &str) -> EsiResult<()> {
+//! async fn authenticate_user(esi: &Esi, code: &str) -> EsiResult<()> {
 //!     // The `code` value here comes from the URL parameters your service
 //!     // is sent following a user's successful SSO.
 //!     //
 //!     // Note that most functions in this crate are async, so you'll need
 //!     // to handle those appropriately.
 //!     //
-//!     // Additionally, this function requires a mutable reference to the
-//!     // struct, as the instance will self-mutate with the additional information
-//!     // from ESI (assuming a successful authorization).
+//!     // The instance stores the tokens from ESI (assuming a successful
+//!     // authorization), which are shared with any clones of it.
 //!     //
 //!     // Once the instance has the auth information, you can use it to make
 //!     // authenticated requests to ESI for the user.
@@ -71,7 +70,7 @@
 //!     esi.get_authorize_url().unwrap()
 //! }
 //!
-//! async fn authenticate_user(esi: &mut Esi, code: &str, pkce_verifier: PkceVerifier) -> EsiResult<()> {
+//! async fn authenticate_user(esi: &Esi, code: &str, pkce_verifier: PkceVerifier) -> EsiResult<()> {
 //!     // The `code` value here comes from the URL parameters your service
 //!     // is sent following a user's successful SSO.
 //!     // The 'pkce_verifier' is randomly generated and returned by the previously called
@@ -80,9 +79,8 @@
 //!     // Note that most functions in this crate are async, so you'll need
 //!     // to handle those appropriately.
 //!     //
-//!     // Additionally, this function requires a mutable reference to the
-//!     // struct, as the instance will self-mutate with the additional information
-//!     // from ESI (assuming a successful authorization).
+//!     // The instance stores the tokens from ESI (assuming a successful
+//!     // authorization), which are shared with any clones of it.
 //!     //
 //!     // Once the instance has the auth information, you can use it to make
 //!     // authenticated requests to ESI for the user.
@@ -106,11 +104,20 @@ compile_error!(
 #[macro_use]
 mod macros;
 
+mod api;
 mod builders;
 mod client;
 mod errors;
 pub mod groups;
 #[cfg(feature = "validate_jwt")]
 mod jwt_util;
+pub mod models;
+#[cfg(feature = "notifications")]
+pub mod notifications;
 mod pkce;
 pub mod prelude;
+#[cfg(test)]
+mod test_server;
+pub mod util;
+#[cfg(any(feature = "vendored_paths", feature = "static_paths", test))]
+mod vendored;
@@ -28,7 +28,7 @@
 /// ```rust,ignore
 /// /// Docs for the generated function
 /// pub async fn function_name(&self) -> EsiResult<Vec<u64>> {
-///     let path = self.esi.get_endpoint_for_op_id("some_operation_id")?;
+///     let path = self.esi.get_wrapped_endpoint("some_operation_id")?;
 ///     self.esi
 ///         .query("GET", RequestType::Public, &path, None, None)
 ///         .await
@@ -66,7 +66,7 @@
 /// ```rust,ignore
 /// /// Docs for the generated function
 /// pub async fn function_name(&self, alliance_id: u64) -> EsiResult<Vec<u64>> {
-///     let path = self.esi.get_endpoint_for_op_id("some_operation_id")?
+///     let path = self.esi.get_wrapped_endpoint("some_operation_id")?
 ///         .replace("{alliance_id}", &alliance_id.to_string());
 ///     self.esi
 ///         .query("GET", RequestType::Public, &path, None, None)
@@ -110,12 +110,11 @@
 /// ```rust,ignore
 /// /// Docs for the generated function
 /// pub async fn function_name(&self, region_id: u64, page: i32, order_type: Option<bool>) -> EsiResult<Vec<u64>> {
-///     let path = self.esi.get_endpoint_for_op_id("some_operation_id")?
+///     let path = self.esi.get_wrapped_endpoint("some_operation_id")?
 ///         .replace("{region_id}", &region_id.to_string());
-///     let params = vec![
+///     let mut params = vec![
 ///         ("page", page.to_string()),
 ///     ]
-///     let mut params = params;
 ///     if let Some(order_type) = order_type {
 ///         params.push(("order_type", order_type.to_string()))
 ///     }
@@ -125,6 +124,44 @@
 ///         .await
 /// }
 /// ```
+///
+/// Lists can be passed as query parameters in two styles, each in its own
+/// semicolon-separated section after the optional parameters:
+///
+/// - `Repeated(name: &[T]) => "key"` sends each item as its own parameter,
+///   as in `?key=1&key=2`.
+/// - `Joined(name: &[T]) => "key"` sends the items as one comma-separated
+///   parameter, as in `?key=1,2`.
+///
+/// In both cases, an empty slice sends nothing. See [crate::groups::RoutesGroup]
+/// for a sample macro call.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use rfesi::prelude::*;
+/// # use rfesi::api_get;
+/// pub struct SomeGroup<'a> {
+///     pub(crate) esi: &'a Esi,
+/// }
+///
+/// impl SomeGroup<'_> {
+///
+///     api_get!(
+///         /// Docs for the generated function
+///         function_name,
+///         "some_operation_id",
+///         RequestType::Public,
+///         Vec<u64>,
+///         (origin: i32) => "{origin}";
+///         Optional(flag: String) => "flag";
+///         Repeated(type_ids: &[i32]) => "type_id";
+///         Joined(avoid: &[i32]) => "avoid"
+///     );
+///
+/// }
+/// # fn main() {}
+/// ```
 #[macro_export]
 macro_rules! api_get {
     (
@@ -139,7 +176,7 @@ macro_rules! api_get {
         pub async fn $fn_name(&self, $( $param: $param_t, )*) -> EsiResult<$ret_type> {
             let path = self
                 .esi
-                .get_endpoint_for_op_id($op_id)?
+                .get_wrapped_endpoint($op_id)?
                 $(
                     .replace($replace, &$param.to_string())
                 )*;
@@ -157,6 +194,8 @@ macro_rules! api_get {
         $( ($param:ident: $param_t:ty) => $replace:literal ),*
         $( ; $( ($qparam:ident: $qparam_t:ty) => $qreplace:literal ),+ )?
         $( ; $( Optional($opt_qparam:ident: $opt_qparam_t:ty) => $opt_qreplace:literal ),+ )?
+        $( ; $( Repeated($rep_qparam:ident: $rep_qparam_t:ty) => $rep_qreplace:literal ),+ )?
+        $( ; $( Joined($join_qparam:ident: $join_qparam_t:ty) => $join_qreplace:literal ),+ )?
     ) => {
         $(#[$m])*
         pub async fn $fn_name(
@@ -164,26 +203,43 @@ macro_rules! api_get {
             $( $param: $param_t, )*
             $($( $qparam: $qparam_t, )*)?
             $($( $opt_qparam: Option<$opt_qparam_t>, )*)?
+            $($( $rep_qparam: $rep_qparam_t, )*)?
+            $($( $join_qparam: $join_qparam_t, )*)?
         ) -> EsiResult<$ret_type> {
             let path = self
                 .esi
-                .get_endpoint_for_op_id($op_id)?
+                .get_wrapped_endpoint($op_id)?
                 $(
                     .replace($replace, &$param.to_string())
                 )*;
-            let params = vec![
+            #[allow(unused_mut)] // only pushed to with optional or list params
+            let mut params = vec![
                 $($(
                     ($qreplace, $qparam.to_string()),
                 )+)?
             ];
             $(
-                let mut params = params; // avoids unnecessary 'mut' warning
                 $(
                     if let Some($opt_qparam) = $opt_qparam {
                         params.push(($opt_qreplace, $opt_qparam.to_string()));
                     }
                 )+
             )?
+            $(
+                $(
+                    for item in $rep_qparam {
+                        params.push(($rep_qreplace, item.to_string()));
+                    }
+                )+
+            )?
+            $(
+                $(
+                    if !$join_qparam.is_empty() {
+                        let joined: Vec<String> = $join_qparam.iter().map(|item| item.to_string()).collect();
+                        params.push(($join_qreplace, joined.join(",")));
+                    }
+                )+
+            )?
             let params: Vec<(&str, &str)> = params.iter().map(|(a, b)| (*a, &**b)).collect();
             self.esi.
                 query("GET", $visibility, &path, Some(&params), None)
@@ -192,6 +248,98 @@ macro_rules! api_get {
     };
 }
 
+/// Create a function for fetching a single page of a
+/// paginated endpoint with a GET request.
+///
+/// Takes the same arguments as the `api_get!` macro, except that
+/// `$ret_type` is the type of each item and there is no `page`
+/// parameter: the generated function takes `page: Option<i32>` after
+/// the path parameters, fetching the first page when `None`, and
+/// returns a [`Paged`](crate::prelude::Paged) with the total page
+/// count. Only optional query parameters are supported.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use rfesi::prelude::*;
+/// # use rfesi::api_get_paged;
+/// pub struct SomeGroup<'a> {
+///     pub(crate) esi: &'a Esi,
+/// }
+///
+/// impl SomeGroup<'_> {
+///
+///     api_get_paged!(
+///         /// Docs for the generated function
+///         function_name,
+///         "some_operation_id",
+///         RequestType::Public,
+///         u64,
+///         (region_id: u64) => "{region_id}";
+///         Optional(type_id: i32) => "type_id"
+///     );
+///
+/// }
+/// # fn main() {}
+/// ```
+/// ## Result:
+///
+/// ```rust,ignore
+/// /// Docs for the generated function
+/// pub async fn function_name(&self, region_id: u64, page: Option<i32>, type_id: Option<i32>) -> EsiResult<Paged<u64>> {
+///     let path = self.esi.get_wrapped_endpoint("some_operation_id")?
+///         .replace("{region_id}", &region_id.to_string());
+///     let mut params = vec![];
+///     if let Some(type_id) = type_id {
+///         params.push(("type_id", type_id.to_string()));
+///     }
+///     let params: Vec<(&str, &str)> = params.iter().map(|(a, b)| (*a, &**b)).collect();
+///     self.esi
+///         .query_page("GET", RequestType::Public, &path, Some(&params), page)
+///         .await
+/// }
+/// ```
+#[macro_export]
+macro_rules! api_get_paged {
+    (
+        $(#[$m:meta])*
+        $fn_name:ident,
+        $op_id:literal,
+        $visibility:expr,
+        $item_type:ty,
+        $( ($param:ident: $param_t:ty) => $replace:literal ),*
+        $( ; $( Optional($opt_qparam:ident: $opt_qparam_t:ty) => $opt_qreplace:literal ),+ )?
+    ) => {
+        $(#[$m])*
+        pub async fn $fn_name(
+            &self,
+            $( $param: $param_t, )*
+            page: Option<i32>,
+            $($( $opt_qparam: Option<$opt_qparam_t>, )*)?
+        ) -> EsiResult<Paged<$item_type>> {
+            let path = self
+                .esi
+                .get_wrapped_endpoint($op_id)?
+                $(
+                    .replace($replace, &$param.to_string())
+                )*;
+            #[allow(unused_mut)] // only pushed to with optional params
+            let mut params: Vec<(&str, String)> = vec![];
+            $(
+                $(
+                    if let Some($opt_qparam) = $opt_qparam {
+                        params.push(($opt_qreplace, $opt_qparam.to_string()));
+                    }
+                )+
+            )?
+            let params: Vec<(&str, &str)> = params.iter().map(|(a, b)| (*a, &**b)).collect();
+            self.esi
+                .query_page("GET", $visibility, &path, Some(&params), page)
+                .await
+        }
+    };
+}
+
 /// Create a function for calling a single endpoint
 /// with a POST request.
 ///
@@ -230,7 +378,7 @@ macro_rules! api_get {
 /// ```rust,ignore
 /// /// Docs for the generated function
 /// pub async fn function_name(&self, alliance_id: u64, ids: &[u64]) -> EsiResult<Vec<u64>> {
-///     let path = self.esi.get_endpoint_for_op_id("some_operation_id")?
+///     let path = self.esi.get_wrapped_endpoint("some_operation_id")?
 ///         .replace("{alliance_id}", &alliance_id.to_string());
 ///     let body = serde_json::to_string(ids);
 ///     self.esi
@@ -253,7 +401,7 @@ macro_rules! api_post {
         pub async fn $fn_name(&self, $( $param: $param_t, )* $body_param: $param_type) -> EsiResult<$ret_type> {
             let path = self
                 .esi
-                .get_endpoint_for_op_id($op_id)?
+                .get_wrapped_endpoint($op_id)?
                 $(
                     .replace($replace, &$param.to_string())
                 )*;
@@ -264,3 +412,113 @@ macro_rules! api_post {
         }
     }
 }
+
+/// Create a function for calling a single endpoint
+/// with a PUT request.
+///
+/// Takes the same arguments as the `api_post!` macro.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use rfesi::prelude::*;
+/// # use rfesi::api_put;
+/// pub struct SomeGroup<'a> {
+///     pub(crate) esi: &'a Esi,
+/// }
+///
+/// impl SomeGroup<'_> {
+///
+///     api_put!(
+///         /// Docs for the generated function
+///         function_name,
+///         "some_operation_id",
+///         RequestType::Authenticated,
+///         (),
+///         (fleet_id: i64) => "{fleet_id}",
+///         settings: &serde_json::Value,
+///     );
+///
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! api_put {
+    (
+        $(#[$m:meta])*
+        $fn_name:ident,
+        $op_id:literal,
+        $visibility:expr,
+        $ret_type:ty,
+        $( ($param:ident: $param_t:ty) => $replace:literal ),*,
+        $body_param:ident: $param_type:ty,
+    ) => {
+        $(#[$m])*
+        pub async fn $fn_name(&self, $( $param: $param_t, )* $body_param: $param_type) -> EsiResult<$ret_type> {
+            let path = self
+                .esi
+                .get_wrapped_endpoint($op_id)?
+                $(
+                    .replace($replace, &$param.to_string())
+                )*;
+            let body = serde_json::to_string($body_param)?;
+            self.esi.
+                query("PUT", $visibility, &path, None, Some(&body))
+                .await
+        }
+    }
+}
+
+/// Create a function for calling a single endpoint
+/// with a DELETE request.
+///
+/// Takes the same arguments as the `api_get!` macro,
+/// without query parameters.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use rfesi::prelude::*;
+/// # use rfesi::api_delete;
+/// pub struct SomeGroup<'a> {
+///     pub(crate) esi: &'a Esi,
+/// }
+///
+/// impl SomeGroup<'_> {
+///
+///     api_delete!(
+///         /// Docs for the generated function
+///         function_name,
+///         "some_operation_id",
+///         RequestType::Authenticated,
+///         (),
+///         (fleet_id: i64) => "{fleet_id}"
+///     );
+///
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! api_delete {
+    (
+        $(#[$m:meta])*
+        $fn_name:ident,
+        $op_id:literal,
+        $visibility:expr,
+        $ret_type:ty,
+        $( ($param:ident: $param_t:ty) => $replace:literal ),*
+    ) => {
+        $(#[$m])*
+        pub async fn $fn_name(&self, $( $param: $param_t, )*) -> EsiResult<$ret_type> {
+            let path = self
+                .esi
+                .get_wrapped_endpoint($op_id)?
+                $(
+                    .replace($replace, &$param.to_string())
+                )*;
+            self.esi.
+                query("DELETE", $visibility, &path, None, None)
+                .await
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+use std::fmt;
+
+use crate::pkce::PkceVerifier;
+use crate::prelude::*;
+use crate::util::constant_time_eq;
+
+/// Type of the access token returned by SSO.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub(crate) enum TokenType {
+    Bearer,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl TokenType {
+    /// Error unless this is a bearer token, which is the only
+    /// type that can be sent in the `Authorization` header.
+    pub(crate) fn ensure_bearer(&self) -> EsiResult<()> {
+        match self {
+            TokenType::Bearer => Ok(()),
+            TokenType::Unknown(other) => Err(EsiError::UnexpectedTokenType(other.clone())),
+        }
+    }
+}
+
+/// Response from SSO when exchanging either a SSO code or a
+/// refresh token for an access token.
+#[derive(Debug, Deserialize)]
+pub(crate) struct AuthenticateResponse {
+    pub(crate) access_token: String,
+    pub(crate) expires_in: u64,
+    pub(crate) token_type: TokenType,
+    pub(crate) refresh_token: Option<String>,
+}
+
+/// AuthenticationInformation contains data needed to complete the requested authentication flow.
+///
+/// The `Debug` output leaves out the state and PKCE verifier, including
+/// the query string of the authorization URL, so that it's safe to log.
+#[derive(Clone)]
+pub struct AuthenticationInformation {
+    /// URL to call/pass to users to initiate an authentication and get an auth code from ESI.
+    pub authorization_url: String,
+    /// If the default feature "random_state" is enabled, the returned state field string will be
+    /// random; otherwise it'll be "rfesi_unused". The ESI docs link to
+    /// [this auth0 page](https://auth0.com/docs/secure/attack-protection/state-parameters)
+    /// to explain. You need to check the state yourself when the response from ESI is received.
+    pub state: String,
+    /// Filled if you've selected PKCE authentication for application.
+    /// You will need it to authenticate using the code received from ESI.
+    pub pkce_verifier: Option<PkceVerifier>,
+}
+
+impl AuthenticationInformation {
+    /// Check the state returned to the callback URL against the one
+    /// sent with the authorization URL, in constant time.
+    pub fn verify_state(&self, returned_state: &str) -> bool {
+        constant_time_eq(self.state.as_bytes(), returned_state.as_bytes())
+    }
+}
+
+impl fmt::Debug for AuthenticationInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let url = match self.authorization_url.split_once('?') {
+            Some((base, _)) => format!("{base}?<redacted>"),
+            None => self.authorization_url.clone(),
+        };
+        f.debug_struct("AuthenticationInformation")
+            .field("authorization_url", &url)
+            .field("state", &"<redacted>")
+            .field(
+                "pkce_verifier",
+                &self.pkce_verifier.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+/// A user's tokens, for moving them between [`Esi`] structs.
+///
+/// See [`Esi::fork_for_user`] and [`Esi::auth_state`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EsiAuthState {
+    /// The access token from ESI, if set.
+    pub access_token: Option<String>,
+    /// The millisecond unix timestamp after which the access token expires, if present.
+    pub access_expiration: Option<i64>,
+    /// The refresh token from ESI, if set.
+    pub refresh_token: Option<String>,
+    /// The scopes that the user granted to the access token.
+    ///
+    /// Set when authenticating or refreshing the access token.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+/// Access token (JWT) payload.
+///
+/// For more information on the content of this struct, see
+/// the [ESI documentation].
+///
+/// [ESI documentation]: https://docs.esi.evetech.net/docs/sso/validating_eve_jwt.html
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[allow(missing_docs)]
+pub struct TokenClaims {
+    pub aud: Vec<String>,
+    pub azp: String,
+    pub exp: i64,
+    pub iat: i64,
+    pub iss: String,
+    pub jti: String,
+    pub kid: String,
+    pub name: String,
+    pub owner: String,
+    pub region: String,
+    pub scp: Option<serde_json::Value>,
+    pub sub: String,
+    pub tenant: String,
+    pub tier: String,
+}
+
+impl TokenClaims {
+    /// Get the scopes granted to the access token.
+    pub fn scopes(&self) -> Vec<String> {
+        scopes_from_claim(self.scp.as_ref())
+    }
+}
+
+/// Get the scopes from the `scp` claim of an access token, which is a
+/// single string rather than an array when only one scope was granted.
+fn scopes_from_claim(scp: Option<&serde_json::Value>) -> Vec<String> {
+    match scp {
+        Some(serde_json::Value::String(scope)) => vec![scope.clone()],
+        Some(serde_json::Value::Array(scopes)) => scopes
+            .iter()
+            .filter_map(|scope| scope.as_str().map(|s| s.to_owned()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Read the granted scopes from the payload of an access token.
+///
+/// The token's signature is not checked; this is only for tokens that
+/// came directly from SSO.
+pub(crate) fn access_token_scopes(access_token: &str) -> EsiResult<Vec<String>> {
+    let payload = access_token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| EsiError::InvalidJWT("Missing payload".to_owned()))?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|e| EsiError::InvalidJWT(e.to_string()))?;
+    let payload: serde_json::Value = serde_json::from_slice(&payload)?;
+    Ok(scopes_from_claim(payload.get("scp")))
+}
+
+/// Response from SSO's token verification endpoint, describing the
+/// character that an access token belongs to.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "PascalCase")]
+pub struct WhoAmIResponse {
+    /// ID of the character.
+    #[serde(rename = "CharacterID")]
+    pub character_id: i32,
+    /// Name of the character.
+    pub character_name: String,
+    /// When the access token expires.
+    pub expires_on: String,
+    /// Space-separated scopes granted to the access token.
+    pub scopes: String,
+    /// Type of the access token, "Character".
+    pub token_type: String,
+    /// Hash that changes when the character is transferred to another account.
+    pub character_owner_hash: String,
+    /// Always "EVE".
+    pub intellectual_property: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        access_token_scopes, AuthenticateResponse, AuthenticationInformation, TokenType,
+        WhoAmIResponse,
+    };
+    use crate::prelude::EsiError;
+
+    #[test]
+    fn test_authenticateresponse_deserialize() {
+        let source = r#"{
+            "access_token": "abc",
+            "expires_in": 1000,
+            "token_type": "Bearer",
+            "refresh_token": "def"
+          }"#;
+        let data: AuthenticateResponse = serde_json::from_str(source).unwrap();
+
+        assert_eq!(data.access_token, "abc");
+        assert_eq!(data.expires_in, 1000);
+        assert_eq!(data.token_type, TokenType::Bearer);
+        assert_eq!(data.refresh_token, Some("def".to_owned()));
+    }
+
+    #[test]
+    fn test_authenticateresponse_deserialize_no_refresh_token() {
+        let source = r#"{
+            "access_token": "abc",
+            "expires_in": 1000,
+            "token_type": "Bearer",
+            "refresh_token": null
+          }"#;
+        let data: AuthenticateResponse = serde_json::from_str(source).unwrap();
+
+        assert_eq!(data.access_token, "abc");
+        assert_eq!(data.expires_in, 1000);
+        assert_eq!(data.refresh_token, None);
+    }
+
+    #[test]
+    fn test_token_type_ensure_bearer() {
+        let token_type: TokenType = serde_json::from_str(r#""Bearer""#).unwrap();
+        assert!(token_type.ensure_bearer().is_ok());
+
+        let token_type: TokenType = serde_json::from_str(r#""MAC""#).unwrap();
+        assert_eq!(token_type, TokenType::Unknown("MAC".to_owned()));
+        assert!(matches!(
+            token_type.ensure_bearer(),
+            Err(EsiError::UnexpectedTokenType(t)) if t == "MAC"
+        ));
+    }
+
+    #[test]
+    fn test_whoamiresponse_deserialize() {
+        let source = r#"{
+            "CharacterID": 2112000000,
+            "CharacterName": "Some Character",
+            "ExpiresOn": "2024-01-01T00:20:00",
+            "Scopes": "esi-skills.read_skills.v1 esi-wallet.read_character_wallet.v1",
+            "TokenType": "Character",
+            "CharacterOwnerHash": "abc123=",
+            "IntellectualProperty": "EVE"
+          }"#;
+        let data: WhoAmIResponse = serde_json::from_str(source).unwrap();
+
+        assert_eq!(data.character_id, 2112000000);
+        assert_eq!(data.character_name, "Some Character");
+        assert_eq!(data.scopes.split(' ').count(), 2);
+    }
+
+    #[test]
+    fn test_authentication_information_debug_redacted() {
+        let info = AuthenticationInformation {
+            authorization_url: "https://login.eveonline.com/v2/oauth/authorize?state=abc123"
+                .to_owned(),
+            state: "abc123".to_owned(),
+            pkce_verifier: Some("verifier456".to_owned()),
+        };
+        let debug = format!("{:?}", info.clone());
+
+        assert!(debug.contains("https://login.eveonline.com/v2/oauth/authorize?<redacted>"));
+        assert!(!debug.contains("abc123"));
+        assert!(!debug.contains("verifier456"));
+    }
+
+    #[test]
+    fn test_authentication_information_verify_state() {
+        let info = AuthenticationInformation {
+            authorization_url: String::new(),
+            state: "abc123".to_owned(),
+            pkce_verifier: None,
+        };
+
+        assert!(info.verify_state("abc123"));
+        assert!(!info.verify_state("abc124"));
+        assert!(!info.verify_state(""));
+    }
+
+    #[test]
+    fn test_access_token_scopes() {
+        use base64::engine::{general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let token = |payload: &str| {
+            format!(
+                "eyJhbGciOiJSUzI1NiJ9.{}.c2lnbmF0dXJl",
+                URL_SAFE_NO_PAD.encode(payload)
+            )
+        };
+        assert_eq!(
+            access_token_scopes(&token(
+                r#"{"scp":["esi-skills.read_skills.v1","esi-wallet.read_character_wallet.v1"]}"#
+            ))
+            .unwrap(),
+            vec![
+                "esi-skills.read_skills.v1",
+                "esi-wallet.read_character_wallet.v1"
+            ]
+        );
+        assert_eq!(
+            access_token_scopes(&token(r#"{"scp":"esi-skills.read_skills.v1"}"#)).unwrap(),
+            vec!["esi-skills.read_skills.v1"]
+        );
+        assert!(access_token_scopes(&token(r#"{"sub":"CHARACTER:EVE:1"}"#))
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            access_token_scopes("not-a-jwt"),
+            Err(EsiError::InvalidJWT(_))
+        ));
+    }
+}
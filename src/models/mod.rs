@@ -0,0 +1,4 @@
+//! Models for EVE SSO and authentication
+
+mod auth;
+pub use auth::*;
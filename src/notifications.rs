@@ -0,0 +1,192 @@
+//! Parsing of character notification text
+//!
+//! The `text` of a [`Notification`] is a YAML document whose fields depend
+//! on the notification's type. [`Notification::parse_text`] gives the raw
+//! YAML, and [`Notification::parse_text_as`] deserializes it into one of the
+//! structs in this module (or your own).
+//!
+//! Requires the "notifications" feature.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+
+use crate::groups::Notification;
+use crate::prelude::*;
+
+/// Seconds between the Windows file time epoch (1601) and the Unix epoch.
+const FILETIME_UNIX_OFFSET_SECS: i64 = 11_644_473_600;
+
+/// Convert a Windows file time, as used for timestamps in notification
+/// text, to a UTC timestamp.
+///
+/// Returns `None` if the value is out of range.
+pub fn filetime_to_datetime(filetime: i64) -> Option<DateTime<Utc>> {
+    let secs = filetime.div_euclid(10_000_000) - FILETIME_UNIX_OFFSET_SECS;
+    let nanos = filetime.rem_euclid(10_000_000) * 100;
+    DateTime::from_timestamp(secs, nanos as u32)
+}
+
+impl Notification {
+    /// Parse the notification's text as YAML.
+    ///
+    /// A notification without text is parsed as `Null`.
+    pub fn parse_text(&self) -> EsiResult<serde_yaml::Value> {
+        self.parse_text_as()
+    }
+
+    /// Parse the notification's text into a struct, such as
+    /// [`StructureUnderAttack`] for a `StructureUnderAttack` notification.
+    ///
+    /// The notification's type is not checked against the struct.
+    pub fn parse_text_as<T: DeserializeOwned>(&self) -> EsiResult<T> {
+        Ok(serde_yaml::from_str(self.text.as_deref().unwrap_or(""))?)
+    }
+}
+
+/// Text of a `StructureUnderAttack` notification.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct StructureUnderAttack {
+    #[serde(rename = "allianceID")]
+    pub alliance_id: Option<i32>,
+    pub alliance_name: Option<String>,
+    pub armor_percentage: f64,
+    #[serde(rename = "charID")]
+    pub char_id: i32,
+    pub corp_name: Option<String>,
+    pub hull_percentage: f64,
+    pub shield_percentage: f64,
+    #[serde(rename = "solarsystemID")]
+    pub solar_system_id: i32,
+    #[serde(rename = "structureID")]
+    pub structure_id: i64,
+    #[serde(rename = "structureTypeID")]
+    pub structure_type_id: i32,
+}
+
+/// Text of a `SovStructureReinforced` notification.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct SovStructureReinforced {
+    pub campaign_event_type: i32,
+    /// Windows file time at which the structure comes out of reinforcement.
+    pub decloak_time: i64,
+    #[serde(rename = "solarSystemID")]
+    pub solar_system_id: i32,
+}
+
+impl SovStructureReinforced {
+    /// Get when the structure comes out of reinforcement.
+    pub fn decloak_time(&self) -> Option<DateTime<Utc>> {
+        filetime_to_datetime(self.decloak_time)
+    }
+}
+
+/// Text of a `WarDeclared` notification.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct WarDeclared {
+    #[serde(rename = "againstID")]
+    pub against_id: i32,
+    pub cost: Option<f64>,
+    #[serde(rename = "declaredByID")]
+    pub declared_by_id: i32,
+    pub delay_hours: Option<i32>,
+    pub hostile_state: Option<bool>,
+    /// Windows file time at which the war was declared.
+    pub time_started: Option<i64>,
+    #[serde(rename = "warHQ")]
+    pub war_hq: Option<String>,
+}
+
+impl WarDeclared {
+    /// Get when the war was declared.
+    pub fn time_started(&self) -> Option<DateTime<Utc>> {
+        self.time_started.and_then(filetime_to_datetime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filetime_to_datetime, SovStructureReinforced, StructureUnderAttack, WarDeclared};
+    use crate::groups::Notification;
+    use chrono::{TimeZone, Utc};
+
+    fn notification(notification_type: &str, text: &str) -> Notification {
+        serde_json::from_value(serde_json::json!({
+            "notification_id": 1,
+            "sender_id": 1000137,
+            "sender_type": "corporation",
+            "text": text,
+            "timestamp": "2024-01-01T00:00:00Z",
+            "type": notification_type,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filetime_to_datetime() {
+        assert_eq!(
+            filetime_to_datetime(116_444_736_000_000_000),
+            Some(Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            filetime_to_datetime(133_485_408_000_000_000),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_structure_under_attack() {
+        let text = "allianceID: 99000001\nallianceLinkData:\n- showinfo\n- 16159\n- 99000001\n\
+            allianceName: Some Alliance\narmorPercentage: 100.0\ncharID: 2112000000\n\
+            corpLinkData:\n- showinfo\n- 2\n- 98000001\ncorpName: Some Corp\n\
+            hullPercentage: 100.0\nshieldPercentage: 94.88\nsolarsystemID: 30000142\n\
+            structureID: &id001 1021000000000\nstructureShowInfoData:\n- showinfo\n- 35832\n\
+            - *id001\nstructureTypeID: 35832\n";
+        let n = notification("StructureUnderAttack", text);
+        let parsed: StructureUnderAttack = n.parse_text_as().unwrap();
+        assert_eq!(parsed.alliance_id, Some(99000001));
+        assert_eq!(parsed.shield_percentage, 94.88);
+        assert_eq!(parsed.structure_id, 1021000000000);
+        assert_eq!(parsed.solar_system_id, 30000142);
+
+        let raw = n.parse_text().unwrap();
+        assert_eq!(
+            raw["structureShowInfoData"][2].as_i64(),
+            Some(1021000000000)
+        );
+    }
+
+    #[test]
+    fn test_parse_sov_structure_reinforced() {
+        let text =
+            "campaignEventType: 2\ndecloakTime: 133485408000000000\nsolarSystemID: 30000142\n";
+        let parsed: SovStructureReinforced = notification("SovStructureReinforced", text)
+            .parse_text_as()
+            .unwrap();
+        assert_eq!(parsed.campaign_event_type, 2);
+        assert_eq!(
+            parsed.decloak_time(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_war_declared() {
+        let text = "againstID: 99000001\ncost: 100000000\ndeclaredByID: 98000001\n\
+            delayHours: 24\nhostileState: false\ntimeStarted: 133485408000000000\n\
+            warHQ: <b>Some Station</b>\nwarHQ_IdType:\n- 1021000000000\n- 35832\n";
+        let parsed: WarDeclared = notification("WarDeclared", text).parse_text_as().unwrap();
+        assert_eq!(parsed.against_id, 99000001);
+        assert_eq!(parsed.declared_by_id, 98000001);
+        assert_eq!(parsed.war_hq.as_deref(), Some("<b>Some Station</b>"));
+        assert!(parsed.time_started().is_some());
+    }
+}
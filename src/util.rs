@@ -0,0 +1,58 @@
+//! Shared helpers
+//!
+//! Most of these are internal, and only compiled in with the endpoint
+//! groups that use them.
+
+/// Maximum number of requests that batch helpers keep in flight at once.
+pub(crate) const MAX_CONCURRENT_REQUESTS: usize = 10;
+
+/// Maximum number of IDs that `post_universe_names` accepts per call.
+#[cfg(feature = "universe")]
+pub(crate) const MAX_IDS_PER_NAMES_CALL: usize = 1_000;
+
+/// Maximum number of IDs that `post_characters_affiliation` accepts per call.
+#[cfg(feature = "killmails")]
+pub(crate) const MAX_IDS_PER_AFFILIATION_CALL: usize = 1_000;
+
+/// Parse an ESI date-time string (RFC 3339) into a UTC timestamp.
+#[cfg(any(
+    feature = "character",
+    feature = "corporation",
+    feature = "planetary_interaction"
+))]
+pub(crate) fn parse_datetime(s: &str) -> crate::errors::EsiResult<chrono::DateTime<chrono::Utc>> {
+    Ok(chrono::DateTime::parse_from_rfc3339(s)?.with_timezone(&chrono::Utc))
+}
+
+/// Compare two secrets, such as OAuth states or refresh tokens, in time
+/// that doesn't depend on where they first differ.
+///
+/// Only the lengths are compared in variable time.
+///
+/// # Example
+/// ```rust
+/// use rfesi::util::constant_time_eq;
+///
+/// assert!(constant_time_eq(b"abc123", b"abc123"));
+/// assert!(!constant_time_eq(b"abc123", b"abc124"));
+/// ```
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::constant_time_eq;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"state", b"state"));
+        assert!(!constant_time_eq(b"state", b"State"));
+        assert!(!constant_time_eq(b"state", b"states"));
+    }
+}
@@ -0,0 +1,412 @@
+//! Vendored `operationId` to path map
+//!
+//! Used in place of the Swagger spec when it can't be fetched from ESI
+//! ("vendored_paths" feature), or instead of fetching it at all
+//! ("static_paths" feature), so that the wrapped endpoints keep working. Only the `operationId`s
+//! in [`WRAPPED_OP_IDS`] are included, and the paths use ESI's `latest`
+//! route rather than a specific spec version.
+//!
+//! To regenerate, fetch the `latest` spec and list its paths, then keep
+//! the entries that are in [`WRAPPED_OP_IDS`]:
+//!
+//! ```sh
+//! curl -s https://esi.evetech.net/latest/swagger.json \
+//!     | jq -r '.paths | to_entries[] | .key as $p | .value[] | "\(.operationId) latest\($p)"' \
+//!     | sort
+//! ```
+//!
+//! [`WRAPPED_OP_IDS`]: crate::groups::WRAPPED_OP_IDS
+
+/// Pairs of `operationId` and URL path, sorted by `operationId`.
+pub(crate) const VENDORED_PATHS: &[(&str, &str)] = &[
+    (
+        "delete_fleets_fleet_id_squads_squad_id",
+        "latest/fleets/{fleet_id}/squads/{squad_id}/",
+    ),
+    (
+        "delete_fleets_fleet_id_wings_wing_id",
+        "latest/fleets/{fleet_id}/wings/{wing_id}/",
+    ),
+    ("get_alliances", "latest/alliances/"),
+    (
+        "get_alliances_alliance_id",
+        "latest/alliances/{alliance_id}/",
+    ),
+    (
+        "get_alliances_alliance_id_corporations",
+        "latest/alliances/{alliance_id}/corporations/",
+    ),
+    (
+        "get_alliances_alliance_id_icons",
+        "latest/alliances/{alliance_id}/icons/",
+    ),
+    (
+        "get_characters_character_id",
+        "latest/characters/{character_id}/",
+    ),
+    (
+        "get_characters_character_id_assets",
+        "latest/characters/{character_id}/assets/",
+    ),
+    (
+        "get_characters_character_id_blueprints",
+        "latest/characters/{character_id}/blueprints/",
+    ),
+    (
+        "get_characters_character_id_clones",
+        "latest/characters/{character_id}/clones/",
+    ),
+    (
+        "get_characters_character_id_contacts",
+        "latest/characters/{character_id}/contacts/",
+    ),
+    (
+        "get_characters_character_id_corporationhistory",
+        "latest/characters/{character_id}/corporationhistory/",
+    ),
+    (
+        "get_characters_character_id_fittings",
+        "latest/characters/{character_id}/fittings/",
+    ),
+    (
+        "get_characters_character_id_implants",
+        "latest/characters/{character_id}/implants/",
+    ),
+    (
+        "get_characters_character_id_industry_jobs",
+        "latest/characters/{character_id}/industry/jobs/",
+    ),
+    (
+        "get_characters_character_id_killmails_recent",
+        "latest/characters/{character_id}/killmails/recent/",
+    ),
+    (
+        "get_characters_character_id_location",
+        "latest/characters/{character_id}/location/",
+    ),
+    (
+        "get_characters_character_id_mail",
+        "latest/characters/{character_id}/mail/",
+    ),
+    (
+        "get_characters_character_id_mail_labels",
+        "latest/characters/{character_id}/mail/labels/",
+    ),
+    (
+        "get_characters_character_id_mining",
+        "latest/characters/{character_id}/mining/",
+    ),
+    (
+        "get_characters_character_id_notifications",
+        "latest/characters/{character_id}/notifications/",
+    ),
+    (
+        "get_characters_character_id_online",
+        "latest/characters/{character_id}/online/",
+    ),
+    (
+        "get_characters_character_id_orders",
+        "latest/characters/{character_id}/orders/",
+    ),
+    (
+        "get_characters_character_id_orders_history",
+        "latest/characters/{character_id}/orders/history/",
+    ),
+    (
+        "get_characters_character_id_planets",
+        "latest/characters/{character_id}/planets/",
+    ),
+    (
+        "get_characters_character_id_planets_planet_id",
+        "latest/characters/{character_id}/planets/{planet_id}/",
+    ),
+    (
+        "get_characters_character_id_portrait",
+        "latest/characters/{character_id}/portrait/",
+    ),
+    (
+        "get_characters_character_id_roles",
+        "latest/characters/{character_id}/roles/",
+    ),
+    (
+        "get_characters_character_id_search",
+        "latest/characters/{character_id}/search/",
+    ),
+    (
+        "get_characters_character_id_ship",
+        "latest/characters/{character_id}/ship/",
+    ),
+    (
+        "get_characters_character_id_skills",
+        "latest/characters/{character_id}/skills/",
+    ),
+    (
+        "get_characters_character_id_wallet",
+        "latest/characters/{character_id}/wallet/",
+    ),
+    (
+        "get_characters_character_id_wallet_journal",
+        "latest/characters/{character_id}/wallet/journal/",
+    ),
+    (
+        "get_characters_character_id_wallet_transactions",
+        "latest/characters/{character_id}/wallet/transactions/",
+    ),
+    (
+        "get_corporations_corporation_id",
+        "latest/corporations/{corporation_id}/",
+    ),
+    (
+        "get_corporations_corporation_id_alliancehistory",
+        "latest/corporations/{corporation_id}/alliancehistory/",
+    ),
+    (
+        "get_corporations_corporation_id_assets",
+        "latest/corporations/{corporation_id}/assets/",
+    ),
+    (
+        "get_corporations_corporation_id_divisions",
+        "latest/corporations/{corporation_id}/divisions/",
+    ),
+    (
+        "get_corporations_corporation_id_members",
+        "latest/corporations/{corporation_id}/members/",
+    ),
+    (
+        "get_corporations_corporation_id_membertracking",
+        "latest/corporations/{corporation_id}/membertracking/",
+    ),
+    (
+        "get_corporations_corporation_id_roles",
+        "latest/corporations/{corporation_id}/roles/",
+    ),
+    (
+        "get_corporations_corporation_id_structures",
+        "latest/corporations/{corporation_id}/structures/",
+    ),
+    (
+        "get_corporations_corporation_id_wallets",
+        "latest/corporations/{corporation_id}/wallets/",
+    ),
+    (
+        "get_corporations_corporation_id_wallets_division_journal",
+        "latest/corporations/{corporation_id}/wallets/{division}/journal/",
+    ),
+    ("get_corporations_npccorps", "latest/corporations/npccorps/"),
+    ("get_dogma_attributes", "latest/dogma/attributes/"),
+    (
+        "get_dogma_attributes_attribute_id",
+        "latest/dogma/attributes/{attribute_id}/",
+    ),
+    ("get_dogma_effects", "latest/dogma/effects/"),
+    (
+        "get_dogma_effects_effect_id",
+        "latest/dogma/effects/{effect_id}/",
+    ),
+    (
+        "get_fleets_fleet_id_members",
+        "latest/fleets/{fleet_id}/members/",
+    ),
+    (
+        "get_fleets_fleet_id_wings",
+        "latest/fleets/{fleet_id}/wings/",
+    ),
+    ("get_fw_leaderboards", "latest/fw/leaderboards/"),
+    (
+        "get_fw_leaderboards_characters",
+        "latest/fw/leaderboards/characters/",
+    ),
+    (
+        "get_fw_leaderboards_corporations",
+        "latest/fw/leaderboards/corporations/",
+    ),
+    ("get_fw_stats", "latest/fw/stats/"),
+    ("get_fw_systems", "latest/fw/systems/"),
+    ("get_fw_wars", "latest/fw/wars/"),
+    ("get_incursions", "latest/incursions/"),
+    ("get_industry_systems", "latest/industry/systems/"),
+    ("get_insurance_prices", "latest/insurance/prices/"),
+    (
+        "get_killmails_killmail_id_killmail_hash",
+        "latest/killmails/{killmail_id}/{killmail_hash}/",
+    ),
+    (
+        "get_loyalty_stores_corporation_id_offers",
+        "latest/loyalty/stores/{corporation_id}/offers/",
+    ),
+    ("get_markets_prices", "latest/markets/prices/"),
+    (
+        "get_markets_region_id_history",
+        "latest/markets/{region_id}/history/",
+    ),
+    (
+        "get_markets_region_id_orders",
+        "latest/markets/{region_id}/orders/",
+    ),
+    (
+        "get_route_origin_destination",
+        "latest/route/{origin}/{destination}/",
+    ),
+    ("get_sovereignty_map", "latest/sovereignty/map/"),
+    (
+        "get_sovereignty_structures",
+        "latest/sovereignty/structures/",
+    ),
+    ("get_status", "latest/status/"),
+    (
+        "get_universe_categories_category_id",
+        "latest/universe/categories/{category_id}/",
+    ),
+    (
+        "get_universe_constellations",
+        "latest/universe/constellations/",
+    ),
+    (
+        "get_universe_constellations_constellation_id",
+        "latest/universe/constellations/{constellation_id}/",
+    ),
+    (
+        "get_universe_groups_group_id",
+        "latest/universe/groups/{group_id}/",
+    ),
+    ("get_universe_regions", "latest/universe/regions/"),
+    (
+        "get_universe_regions_region_id",
+        "latest/universe/regions/{region_id}/",
+    ),
+    (
+        "get_universe_stations_station_id",
+        "latest/universe/stations/{station_id}/",
+    ),
+    ("get_universe_structures", "latest/universe/structures/"),
+    (
+        "get_universe_structures_structure_id",
+        "latest/universe/structures/{structure_id}/",
+    ),
+    ("get_universe_systems", "latest/universe/systems/"),
+    (
+        "get_universe_systems_system_id",
+        "latest/universe/systems/{system_id}/",
+    ),
+    ("get_universe_types", "latest/universe/types/"),
+    (
+        "get_universe_types_type_id",
+        "latest/universe/types/{type_id}/",
+    ),
+    ("get_wars", "latest/wars/"),
+    ("get_wars_war_id", "latest/wars/{war_id}/"),
+    (
+        "post_characters_affiliation",
+        "latest/characters/affiliation/",
+    ),
+    (
+        "post_characters_character_id_assets_locations",
+        "latest/characters/{character_id}/assets/locations/",
+    ),
+    (
+        "post_characters_character_id_assets_names",
+        "latest/characters/{character_id}/assets/names/",
+    ),
+    (
+        "post_characters_character_id_contacts",
+        "latest/characters/{character_id}/contacts/",
+    ),
+    (
+        "post_characters_character_id_fittings",
+        "latest/characters/{character_id}/fittings/",
+    ),
+    (
+        "post_corporations_corporation_id_assets_locations",
+        "latest/corporations/{corporation_id}/assets/locations/",
+    ),
+    (
+        "post_corporations_corporation_id_assets_names",
+        "latest/corporations/{corporation_id}/assets/names/",
+    ),
+    (
+        "post_fleets_fleet_id_wings",
+        "latest/fleets/{fleet_id}/wings/",
+    ),
+    (
+        "post_fleets_fleet_id_wings_wing_id_squads",
+        "latest/fleets/{fleet_id}/wings/{wing_id}/squads/",
+    ),
+    (
+        "post_ui_openwindow_marketdetails",
+        "latest/ui/openwindow/marketdetails/",
+    ),
+    ("post_universe_ids", "latest/universe/ids/"),
+    ("post_universe_names", "latest/universe/names/"),
+    (
+        "put_characters_character_id_contacts",
+        "latest/characters/{character_id}/contacts/",
+    ),
+    (
+        "put_fleets_fleet_id_squads_squad_id",
+        "latest/fleets/{fleet_id}/squads/{squad_id}/",
+    ),
+    (
+        "put_fleets_fleet_id_wings_wing_id",
+        "latest/fleets/{fleet_id}/wings/{wing_id}/",
+    ),
+];
+
+/// Build the lookup of `operationId` to URL path from the vendored paths.
+#[cfg(any(feature = "vendored_paths", test))]
+pub(crate) fn vendored_op_id_map() -> std::collections::HashMap<String, String> {
+    VENDORED_PATHS
+        .iter()
+        .map(|(op_id, path)| ((*op_id).to_owned(), (*path).to_owned()))
+        .collect()
+}
+
+/// Get the vendored URL path for an `operationId`.
+#[cfg(any(feature = "static_paths", test))]
+pub(crate) fn vendored_path(op_id: &str) -> Option<&'static str> {
+    VENDORED_PATHS
+        .binary_search_by_key(&op_id, |(op_id, _)| op_id)
+        .ok()
+        .map(|i| VENDORED_PATHS[i].1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{vendored_op_id_map, vendored_path, VENDORED_PATHS};
+    use crate::groups::WRAPPED_OP_IDS;
+
+    #[test]
+    fn test_vendored_paths_match_wrapped_op_ids() {
+        let op_ids: Vec<_> = VENDORED_PATHS.iter().map(|(op_id, _)| *op_id).collect();
+        assert_eq!(op_ids, WRAPPED_OP_IDS);
+    }
+
+    #[test]
+    fn test_vendored_paths_format() {
+        for (op_id, path) in VENDORED_PATHS {
+            assert!(
+                path.starts_with("latest/") && path.ends_with('/'),
+                "bad path '{path}' for '{op_id}'"
+            );
+        }
+    }
+
+    #[test]
+    fn test_vendored_op_id_map() {
+        let map = vendored_op_id_map();
+        assert_eq!(map.len(), VENDORED_PATHS.len());
+        assert_eq!(
+            map.get("get_characters_character_id").unwrap(),
+            "latest/characters/{character_id}/"
+        );
+    }
+
+    #[test]
+    fn test_vendored_path() {
+        assert_eq!(vendored_path("get_status"), Some("latest/status/"));
+        assert_eq!(
+            vendored_path("put_characters_character_id_contacts"),
+            Some("latest/characters/{character_id}/contacts/")
+        );
+        assert_eq!(vendored_path("get_alliances_alliance_id_contacts"), None);
+    }
+}